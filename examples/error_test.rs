@@ -1,30 +1,37 @@
 //! Test error handling against the live API.
 //!
 //! Run with: EARNINGSFEED_API_KEY=your_key cargo run --example error_test
+//!
+//! Set `EARNINGSFEED_CASSETTE_PATH` and `EARNINGSFEED_CASSETTE_MODE=record`
+//! to record the session, or `EARNINGSFEED_CASSETTE_MODE=replay` to run it
+//! offline from the recording.
 
-use earningsfeed::{EarningsFeed, Error};
+use earningsfeed::{ClientConfig, EarningsFeed, Error};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Test 1: Invalid API key (401)
     println!("=== Testing Error Handling ===\n");
 
+    // A single client, so that a cassette records the whole session.
+    let config = ClientConfig::from_env()?;
+    let api_key = config.api_key.clone();
+    let client = EarningsFeed::with_config(config)?;
+
     println!("Test 1: Invalid API key...");
-    let bad_client = EarningsFeed::new("invalid_key_12345")?;
-    match bad_client.companies().get(320193).await {
+    client.set_api_key("invalid_key_12345")?;
+    match client.companies().get(320193).await {
         Err(Error::Authentication) => println!("✓ Got Authentication error as expected"),
         Err(e) => println!("✗ Got unexpected error: {:?}", e),
         Ok(_) => println!("✗ Should have failed but succeeded"),
     }
+    client.set_api_key(api_key)?;
 
     // Test 2: Not found (404)
-    let api_key = std::env::var("EARNINGSFEED_API_KEY")
-        .expect("EARNINGSFEED_API_KEY environment variable required");
-    let client = EarningsFeed::new(&api_key)?;
 
     println!("\nTest 2: Non-existent filing (404)...");
     match client.filings().get("0000000000-00-000000").await {
-        Err(Error::NotFound { path }) => println!("✓ Got NotFound error for path: {}", path),
+        Err(Error::NotFound { path, .. }) => println!("✓ Got NotFound error for path: {}", path),
         Err(e) => println!("✗ Got unexpected error: {:?}", e),
         Ok(_) => println!("✗ Should have failed but succeeded"),
     }
@@ -32,7 +39,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Test 3: Non-existent company (404)
     println!("\nTest 3: Non-existent company (404)...");
     match client.companies().get(999999999).await {
-        Err(Error::NotFound { path }) => println!("✓ Got NotFound error for path: {}", path),
+        Err(Error::NotFound { path, .. }) => println!("✓ Got NotFound error for path: {}", path),
         Err(e) => println!("✗ Got unexpected error: {:?}", e),
         Ok(_) => println!("✗ Should have failed but succeeded"),
     }
@@ -44,7 +51,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .build();
 
     match client.filings().list(&params).await {
-        Err(Error::Validation { message }) => println!("✓ Got Validation error: {}", message),
+        Err(Error::Validation { message, .. }) => println!("✓ Got Validation error: {}", message),
         Err(e) => println!("? Got error (may be valid): {:?}", e),
         Ok(_) => println!("? Request succeeded (limit may be valid)"),
     }
@@ -1,6 +1,10 @@
 //! Integration test against the live EarningsFeed API.
 //!
 //! Run with: EARNINGSFEED_API_KEY=your_key cargo run --example integration_test
+//!
+//! Set `EARNINGSFEED_CASSETTE_PATH` and `EARNINGSFEED_CASSETTE_MODE=record`
+//! to record the session, or `EARNINGSFEED_CASSETTE_MODE=replay` to run it
+//! offline from the recording.
 
 use earningsfeed::{EarningsFeed, ListFilingsParams, ListInsiderParams, ListInstitutionalParams, SearchCompaniesParams};
 use futures::StreamExt;
@@ -8,11 +12,8 @@ use std::pin::pin;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let api_key = std::env::var("EARNINGSFEED_API_KEY")
-        .expect("EARNINGSFEED_API_KEY environment variable required");
-
     println!("Creating client...");
-    let client = EarningsFeed::new(&api_key)?;
+    let client = EarningsFeed::from_env()?;
     println!("✓ Client created\n");
 
     // Test 1: List filings
@@ -0,0 +1,145 @@
+//! Trait abstraction over the API.
+//!
+//! This module provides [`EarningsFeedApi`], which application code can
+//! depend on instead of the concrete [`EarningsFeed`] client so that it can
+//! be tested against an in-memory implementation.
+
+use futures::future::{BoxFuture, FutureExt};
+
+use crate::client::EarningsFeed;
+use crate::error::Result;
+use crate::models::{
+    Company, CompanySearchResult, Filing, FilingDetail, InsiderTransaction, InstitutionalHolding,
+    ListFilingsParams, ListInsiderParams, ListInstitutionalParams, PaginatedResponse,
+    SearchCompaniesParams,
+};
+
+/// The API operations of the EarningsFeed client.
+///
+/// [`EarningsFeed`] implements this trait by sending requests, and
+/// `testing::MockEarningsFeed` (with the `testing` feature) by serving
+/// canned responses. Code written against `&dyn EarningsFeedApi` or a
+/// generic `impl EarningsFeedApi` works with both.
+///
+/// # Example
+///
+/// ```rust
+/// use earningsfeed::{EarningsFeedApi, ListFilingsParams, Result};
+///
+/// async fn latest_form_type(api: &dyn EarningsFeedApi, ticker: &str) -> Result<Option<String>> {
+///     let params = ListFilingsParams::builder().ticker(ticker).limit(1).build();
+///     let page = api.list_filings(&params).await?;
+///     Ok(page.items.into_iter().next().map(|filing| filing.form_type))
+/// }
+/// ```
+pub trait EarningsFeedApi: Send + Sync {
+    /// List filings, like `filings().list()` on [`EarningsFeed`].
+    fn list_filings<'a>(
+        &'a self,
+        params: &'a ListFilingsParams,
+    ) -> BoxFuture<'a, Result<PaginatedResponse<Filing>>>;
+
+    /// Get a filing by accession number, like `filings().get()` on
+    /// [`EarningsFeed`].
+    fn get_filing<'a>(&'a self, accession_number: &'a str) -> BoxFuture<'a, Result<FilingDetail>>;
+
+    /// List insider transactions, like `insider().list()` on [`EarningsFeed`].
+    fn list_insider<'a>(
+        &'a self,
+        params: &'a ListInsiderParams,
+    ) -> BoxFuture<'a, Result<PaginatedResponse<InsiderTransaction>>>;
+
+    /// List institutional holdings, like `institutional().list()` on
+    /// [`EarningsFeed`].
+    fn list_institutional<'a>(
+        &'a self,
+        params: &'a ListInstitutionalParams,
+    ) -> BoxFuture<'a, Result<PaginatedResponse<InstitutionalHolding>>>;
+
+    /// Search for companies, like `companies().search()` on [`EarningsFeed`].
+    fn search_companies<'a>(
+        &'a self,
+        params: &'a SearchCompaniesParams,
+    ) -> BoxFuture<'a, Result<PaginatedResponse<CompanySearchResult>>>;
+
+    /// Get a company by CIK, like `companies().get()` on [`EarningsFeed`].
+    fn get_company(&self, cik: u64) -> BoxFuture<'_, Result<Company>>;
+}
+
+impl EarningsFeedApi for EarningsFeed {
+    fn list_filings<'a>(
+        &'a self,
+        params: &'a ListFilingsParams,
+    ) -> BoxFuture<'a, Result<PaginatedResponse<Filing>>> {
+        async move { self.filings().list(params).await }.boxed()
+    }
+
+    fn get_filing<'a>(&'a self, accession_number: &'a str) -> BoxFuture<'a, Result<FilingDetail>> {
+        async move { self.filings().get(accession_number).await }.boxed()
+    }
+
+    fn list_insider<'a>(
+        &'a self,
+        params: &'a ListInsiderParams,
+    ) -> BoxFuture<'a, Result<PaginatedResponse<InsiderTransaction>>> {
+        async move { self.insider().list(params).await }.boxed()
+    }
+
+    fn list_institutional<'a>(
+        &'a self,
+        params: &'a ListInstitutionalParams,
+    ) -> BoxFuture<'a, Result<PaginatedResponse<InstitutionalHolding>>> {
+        async move { self.institutional().list(params).await }.boxed()
+    }
+
+    fn search_companies<'a>(
+        &'a self,
+        params: &'a SearchCompaniesParams,
+    ) -> BoxFuture<'a, Result<PaginatedResponse<CompanySearchResult>>> {
+        async move { self.companies().search(params).await }.boxed()
+    }
+
+    fn get_company(&self, cik: u64) -> BoxFuture<'_, Result<Company>> {
+        async move { self.companies().get(cik).await }.boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn first_ticker(api: &dyn EarningsFeedApi, query: &str) -> Result<Option<String>> {
+        let params = SearchCompaniesParams::builder().q(query).build();
+        let page = api.search_companies(&params).await?;
+        Ok(page.items.into_iter().next().and_then(|c| c.ticker))
+    }
+
+    #[tokio::test]
+    async fn test_client_implements_trait() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/companies/search"))
+            .and(query_param("q", "apple"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [{ "cik": 320193, "name": "Apple Inc.", "ticker": "AAPL" }],
+                "nextCursor": null,
+                "hasMore": false
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .build()
+            .and_then(EarningsFeed::with_config)
+            .unwrap();
+
+        let ticker = first_ticker(&client, "apple").await.unwrap();
+        assert_eq!(ticker.as_deref(), Some("AAPL"));
+    }
+}
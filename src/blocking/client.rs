@@ -0,0 +1,207 @@
+//! Blocking EarningsFeed API client.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use tokio::runtime::Runtime;
+
+use super::resources::{
+    CompaniesResource, FilingsResource, InsiderResource, InstitutionalResource,
+};
+use crate::cache::ResponseCache;
+use crate::circuit_breaker::CircuitState;
+use crate::config::{ClientConfig, ClientConfigBuilder};
+use crate::credentials::ApiKey;
+use crate::error::{Error, Result};
+use crate::metrics::MetricsSnapshot;
+use crate::response::RateLimitInfo;
+
+/// Blocking client for the EarningsFeed API.
+///
+/// Offers the same resources as the async [`EarningsFeed`](crate::EarningsFeed)
+/// client, with blocking methods. Clones share the underlying connection
+/// pool, runtime, rate limiter and caches.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use earningsfeed::blocking::EarningsFeed;
+///
+/// let client = EarningsFeed::new("your_api_key")?;
+/// let company = client.companies().get(320193)?;
+/// println!("{}", company.name);
+/// ```
+#[derive(Clone)]
+pub struct EarningsFeed {
+    inner: crate::EarningsFeed,
+    runtime: Arc<Runtime>,
+}
+
+impl EarningsFeed {
+    /// Create a new blocking client with the given API key.
+    ///
+    /// Uses default configuration (base URL: `https://earningsfeed.com`, timeout: 30s).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API key is empty or if the HTTP client or
+    /// runtime cannot be created.
+    pub fn new(api_key: impl Into<ApiKey>) -> Result<Self> {
+        let config = ClientConfig::builder().api_key(api_key).build()?;
+        Self::with_config(config)
+    }
+
+    /// Create a new blocking client with custom configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client or runtime cannot be created.
+    pub fn with_config(config: ClientConfig) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| Error::Config(format!("cannot create runtime: {}", e)))?;
+
+        Ok(Self {
+            inner: crate::EarningsFeed::with_config(config)?,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Create a new blocking client configured from environment variables.
+    ///
+    /// See [`crate::EarningsFeed::from_env`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a variable is malformed, the API key is not set,
+    /// or the HTTP client or runtime cannot be created.
+    pub fn from_env() -> Result<Self> {
+        Self::with_config(ClientConfig::from_env()?)
+    }
+
+    /// Create a configuration builder.
+    ///
+    /// Convenience method for creating a new [`ClientConfig`] builder.
+    #[must_use]
+    pub fn builder() -> ClientConfigBuilder {
+        ClientConfig::builder()
+    }
+
+    /// Get the base URL for API requests.
+    #[must_use]
+    pub fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+
+    /// Get the total number of retries performed by this client.
+    #[must_use]
+    pub fn retry_count(&self) -> u64 {
+        self.inner.retry_count()
+    }
+
+    /// Get the most recently observed rate-limit state.
+    #[must_use]
+    pub fn rate_limit(&self) -> Option<RateLimitInfo> {
+        self.inner.rate_limit()
+    }
+
+    /// Get the in-memory response cache, if enabled.
+    #[must_use]
+    pub fn cache(&self) -> Option<&ResponseCache> {
+        self.inner.cache()
+    }
+
+    /// Get the state of the circuit breaker, if enabled.
+    #[must_use]
+    pub fn circuit_state(&self) -> Option<CircuitState> {
+        self.inner.circuit_state()
+    }
+
+    /// Get a snapshot of the per-endpoint metrics, if enabled.
+    #[must_use]
+    pub fn metrics(&self) -> Option<MetricsSnapshot> {
+        self.inner.metrics()
+    }
+
+    /// Reset the per-endpoint metrics to zero, if enabled.
+    pub fn reset_metrics(&self) {
+        self.inner.reset_metrics();
+    }
+
+    /// Replace the API key used for subsequent requests.
+    ///
+    /// See [`crate::EarningsFeed::set_api_key`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is empty or not a valid header value.
+    pub fn set_api_key(&self, api_key: impl Into<ApiKey>) -> Result<()> {
+        self.inner.set_api_key(api_key)
+    }
+
+    /// Get the underlying async client.
+    #[must_use]
+    pub fn as_async(&self) -> &crate::EarningsFeed {
+        &self.inner
+    }
+
+    /// Access the filings resource.
+    #[must_use]
+    pub fn filings(&self) -> FilingsResource<'_> {
+        FilingsResource::new(self)
+    }
+
+    /// Access the insider transactions resource.
+    #[must_use]
+    pub fn insider(&self) -> InsiderResource<'_> {
+        InsiderResource::new(self)
+    }
+
+    /// Access the institutional holdings resource.
+    #[must_use]
+    pub fn institutional(&self) -> InstitutionalResource<'_> {
+        InstitutionalResource::new(self)
+    }
+
+    /// Access the companies resource.
+    #[must_use]
+    pub fn companies(&self) -> CompaniesResource<'_> {
+        CompaniesResource::new(self)
+    }
+
+    /// Run a future of the async client to completion.
+    pub(super) fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+}
+
+impl std::fmt::Debug for EarningsFeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EarningsFeed")
+            .field("base_url", &self.inner.base_url())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_with_valid_api_key() {
+        let client = EarningsFeed::new("test_api_key").unwrap();
+        assert_eq!(client.base_url(), "https://earningsfeed.com");
+    }
+
+    #[test]
+    fn test_new_with_empty_api_key_fails() {
+        assert!(matches!(EarningsFeed::new(""), Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn test_client_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<EarningsFeed>();
+    }
+}
@@ -0,0 +1,35 @@
+//! Blocking (synchronous) client for the EarningsFeed API.
+//!
+//! Enabled with the `blocking` feature. The blocking client wraps the async
+//! [`EarningsFeed`](crate::EarningsFeed) client and drives it on an internal
+//! single-threaded tokio runtime, so callers do not need a runtime of their
+//! own. It shares the models, parameters and error types of the async API.
+//!
+//! The blocking client must not be used from within an async runtime.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use earningsfeed::blocking::EarningsFeed;
+//! use earningsfeed::ListFilingsParams;
+//!
+//! fn main() -> Result<(), earningsfeed::Error> {
+//!     let client = EarningsFeed::new("your_api_key")?;
+//!
+//!     let params = ListFilingsParams::builder().ticker("AAPL").build();
+//!     for filing in client.filings().iter(params).take(50) {
+//!         let filing = filing?;
+//!         println!("{}: {}", filing.form_type, filing.title);
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+mod client;
+mod resources;
+
+pub use client::EarningsFeed;
+pub use resources::{
+    CompaniesResource, FilingsResource, InsiderResource, InstitutionalResource, PageIter,
+};
@@ -0,0 +1,480 @@
+//! Blocking API resources.
+//!
+//! Each resource wraps its async counterpart, blocking on every call.
+
+use futures::stream::{BoxStream, StreamExt};
+
+use super::client::EarningsFeed;
+use crate::error::Result;
+use crate::models::{
+    Company, CompanySearchResult, Filing, FilingDetail, InsiderTransaction, InstitutionalHolding,
+    ListFilingsParams, ListInsiderParams, ListInstitutionalParams, PaginatedResponse,
+    SearchCompaniesParams,
+};
+use crate::options::RequestOptions;
+use crate::pagination::Paginator;
+use crate::resources;
+use crate::response::Response;
+
+/// Iterator over all items of a paginated endpoint.
+///
+/// Drives the [`Paginator`] of the async resource, so it stops with
+/// [`Error::RepeatedCursor`](crate::Error::RepeatedCursor) if the server
+/// returns a cursor that was already followed. Pages are fetched lazily:
+/// the next page is requested only once the items of the current page have
+/// been consumed. After an error is returned, the iterator ends.
+pub struct PageIter<'a, T> {
+    client: &'a EarningsFeed,
+    pages: BoxStream<'a, Result<PaginatedResponse<T>>>,
+    items: std::vec::IntoIter<T>,
+    done: bool,
+}
+
+impl<'a, T: Send + 'static> PageIter<'a, T> {
+    /// Create an iterator over the pages of `paginator`.
+    fn new(client: &'a EarningsFeed, paginator: Paginator<'a, T>) -> Self {
+        Self {
+            client,
+            pages: paginator.pages().boxed(),
+            items: Vec::new().into_iter(),
+            done: false,
+        }
+    }
+}
+
+impl<T> Iterator for PageIter<'_, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(Ok(item));
+            }
+            if self.done {
+                return None;
+            }
+
+            match self.client.block_on(self.pages.next()) {
+                Some(Ok(page)) => self.items = page.items.into_iter(),
+                Some(Err(error)) => {
+                    self.done = true;
+                    return Some(Err(error));
+                }
+                None => self.done = true,
+            }
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for PageIter<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PageIter")
+            .field("buffered", &self.items.len())
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+/// Blocking resource for accessing SEC filings.
+///
+/// Obtain an instance via [`EarningsFeed::filings()`].
+pub struct FilingsResource<'a> {
+    client: &'a EarningsFeed,
+    options: RequestOptions,
+}
+
+impl<'a> FilingsResource<'a> {
+    /// Create a new filings resource.
+    pub(super) fn new(client: &'a EarningsFeed) -> Self {
+        Self {
+            client,
+            options: RequestOptions::default(),
+        }
+    }
+
+    /// Apply per-request options to every call of this resource.
+    ///
+    /// See [`RequestOptions`] for details.
+    #[must_use]
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// The async resource, with this resource's options.
+    fn inner(&self) -> resources::FilingsResource<'a> {
+        self.client
+            .as_async()
+            .filings()
+            .with_options(self.options.clone())
+    }
+
+    /// List filings with optional filters.
+    ///
+    /// Returns a paginated response. Use [`iter`](Self::iter) for automatic pagination.
+    pub fn list(&self, params: &ListFilingsParams) -> Result<PaginatedResponse<Filing>> {
+        self.client.block_on(self.inner().list(params))
+    }
+
+    /// List filings, returning response metadata alongside the data.
+    pub fn list_with_meta(
+        &self,
+        params: &ListFilingsParams,
+    ) -> Result<Response<PaginatedResponse<Filing>>> {
+        self.client.block_on(self.inner().list_with_meta(params))
+    }
+
+    /// Get a specific filing by accession number.
+    pub fn get(&self, accession_number: &str) -> Result<FilingDetail> {
+        self.client.block_on(self.inner().get(accession_number))
+    }
+
+    /// Get a specific filing, returning response metadata alongside the data.
+    pub fn get_with_meta(&self, accession_number: &str) -> Result<Response<FilingDetail>> {
+        self.client
+            .block_on(self.inner().get_with_meta(accession_number))
+    }
+
+    /// Iterate over all filings matching the given parameters.
+    ///
+    /// Returns an iterator that fetches pages lazily.
+    pub fn iter(&self, params: ListFilingsParams) -> PageIter<'a, Filing> {
+        PageIter::new(self.client, self.inner().paginate(params))
+    }
+}
+
+/// Blocking resource for accessing insider transactions.
+///
+/// Obtain an instance via [`EarningsFeed::insider()`].
+pub struct InsiderResource<'a> {
+    client: &'a EarningsFeed,
+    options: RequestOptions,
+}
+
+impl<'a> InsiderResource<'a> {
+    /// Create a new insider resource.
+    pub(super) fn new(client: &'a EarningsFeed) -> Self {
+        Self {
+            client,
+            options: RequestOptions::default(),
+        }
+    }
+
+    /// Apply per-request options to every call of this resource.
+    ///
+    /// See [`RequestOptions`] for details.
+    #[must_use]
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// The async resource, with this resource's options.
+    fn inner(&self) -> resources::InsiderResource<'a> {
+        self.client
+            .as_async()
+            .insider()
+            .with_options(self.options.clone())
+    }
+
+    /// List insider transactions with optional filters.
+    ///
+    /// Returns a paginated response. Use [`iter`](Self::iter) for automatic pagination.
+    pub fn list(
+        &self,
+        params: &ListInsiderParams,
+    ) -> Result<PaginatedResponse<InsiderTransaction>> {
+        self.client.block_on(self.inner().list(params))
+    }
+
+    /// List insider transactions, returning response metadata alongside the data.
+    pub fn list_with_meta(
+        &self,
+        params: &ListInsiderParams,
+    ) -> Result<Response<PaginatedResponse<InsiderTransaction>>> {
+        self.client.block_on(self.inner().list_with_meta(params))
+    }
+
+    /// Iterate over all insider transactions matching the given parameters.
+    ///
+    /// Returns an iterator that fetches pages lazily.
+    pub fn iter(&self, params: ListInsiderParams) -> PageIter<'a, InsiderTransaction> {
+        PageIter::new(self.client, self.inner().paginate(params))
+    }
+}
+
+/// Blocking resource for accessing institutional holdings.
+///
+/// Obtain an instance via [`EarningsFeed::institutional()`].
+pub struct InstitutionalResource<'a> {
+    client: &'a EarningsFeed,
+    options: RequestOptions,
+}
+
+impl<'a> InstitutionalResource<'a> {
+    /// Create a new institutional resource.
+    pub(super) fn new(client: &'a EarningsFeed) -> Self {
+        Self {
+            client,
+            options: RequestOptions::default(),
+        }
+    }
+
+    /// Apply per-request options to every call of this resource.
+    ///
+    /// See [`RequestOptions`] for details.
+    #[must_use]
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// The async resource, with this resource's options.
+    fn inner(&self) -> resources::InstitutionalResource<'a> {
+        self.client
+            .as_async()
+            .institutional()
+            .with_options(self.options.clone())
+    }
+
+    /// List institutional holdings with optional filters.
+    ///
+    /// Returns a paginated response. Use [`iter`](Self::iter) for automatic pagination.
+    pub fn list(
+        &self,
+        params: &ListInstitutionalParams,
+    ) -> Result<PaginatedResponse<InstitutionalHolding>> {
+        self.client.block_on(self.inner().list(params))
+    }
+
+    /// List institutional holdings, returning response metadata alongside the data.
+    pub fn list_with_meta(
+        &self,
+        params: &ListInstitutionalParams,
+    ) -> Result<Response<PaginatedResponse<InstitutionalHolding>>> {
+        self.client.block_on(self.inner().list_with_meta(params))
+    }
+
+    /// Iterate over all institutional holdings matching the given parameters.
+    ///
+    /// Returns an iterator that fetches pages lazily.
+    pub fn iter(&self, params: ListInstitutionalParams) -> PageIter<'a, InstitutionalHolding> {
+        PageIter::new(self.client, self.inner().paginate(params))
+    }
+}
+
+/// Blocking resource for accessing company data.
+///
+/// Obtain an instance via [`EarningsFeed::companies()`].
+pub struct CompaniesResource<'a> {
+    client: &'a EarningsFeed,
+    options: RequestOptions,
+}
+
+impl<'a> CompaniesResource<'a> {
+    /// Create a new companies resource.
+    pub(super) fn new(client: &'a EarningsFeed) -> Self {
+        Self {
+            client,
+            options: RequestOptions::default(),
+        }
+    }
+
+    /// Apply per-request options to every call of this resource.
+    ///
+    /// See [`RequestOptions`] for details.
+    #[must_use]
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// The async resource, with this resource's options.
+    fn inner(&self) -> resources::CompaniesResource<'a> {
+        self.client
+            .as_async()
+            .companies()
+            .with_options(self.options.clone())
+    }
+
+    /// Get a company by CIK.
+    pub fn get(&self, cik: u64) -> Result<Company> {
+        self.client.block_on(self.inner().get(cik))
+    }
+
+    /// Get a company by CIK, returning response metadata alongside the data.
+    pub fn get_with_meta(&self, cik: u64) -> Result<Response<Company>> {
+        self.client.block_on(self.inner().get_with_meta(cik))
+    }
+
+    /// Search for companies.
+    ///
+    /// Returns a paginated response. Use [`iter_search`](Self::iter_search) for automatic pagination.
+    pub fn search(
+        &self,
+        params: &SearchCompaniesParams,
+    ) -> Result<PaginatedResponse<CompanySearchResult>> {
+        self.client.block_on(self.inner().search(params))
+    }
+
+    /// Search for companies, returning response metadata alongside the data.
+    pub fn search_with_meta(
+        &self,
+        params: &SearchCompaniesParams,
+    ) -> Result<Response<PaginatedResponse<CompanySearchResult>>> {
+        self.client.block_on(self.inner().search_with_meta(params))
+    }
+
+    /// Iterate over all companies matching the search parameters.
+    ///
+    /// Returns an iterator that fetches pages lazily.
+    pub fn iter_search(&self, params: SearchCompaniesParams) -> PageIter<'a, CompanySearchResult> {
+        PageIter::new(self.client, self.inner().paginate_search(params))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// Start a mock server on its own runtime, as the blocking client
+    /// cannot be used from within one.
+    fn start_server() -> (tokio::runtime::Runtime, MockServer) {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let server = runtime.block_on(MockServer::start());
+        (runtime, server)
+    }
+
+    fn setup_client(mock_server: &MockServer) -> EarningsFeed {
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .build()
+            .unwrap();
+        EarningsFeed::with_config(config).unwrap()
+    }
+
+    fn holding(cusip: &str) -> serde_json::Value {
+        serde_json::json!({
+            "cusip": cusip,
+            "issuerName": "APPLE INC",
+            "classTitle": "COM",
+            "value": "5000000",
+            "shares": "25000",
+            "sharesType": "SH",
+            "investmentDiscretion": "SOLE",
+            "managerCik": 102909,
+            "managerName": "BERKSHIRE HATHAWAY INC",
+            "reportPeriodDate": "2024-09-30",
+            "filedAt": "2024-11-14T16:30:00Z",
+            "accessionNumber": "0000950123-24-012345"
+        })
+    }
+
+    #[test]
+    fn test_get_company() {
+        let (runtime, mock_server) = start_server();
+        runtime.block_on(
+            Mock::given(method("GET"))
+                .and(path("/api/v1/companies/999999999"))
+                .respond_with(ResponseTemplate::new(404))
+                .mount(&mock_server),
+        );
+
+        let client = setup_client(&mock_server);
+        let result = client.companies().get(999999999);
+        assert!(matches!(result, Err(Error::NotFound { .. })));
+    }
+
+    #[test]
+    fn test_iter_paginates_lazily() {
+        let (runtime, mock_server) = start_server();
+        runtime.block_on(async {
+            Mock::given(method("GET"))
+                .and(path("/api/v1/institutional/holdings"))
+                .and(query_param("cursor", "page2"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "items": [holding("3")],
+                    "nextCursor": null,
+                    "hasMore": false
+                })))
+                .mount(&mock_server)
+                .await;
+
+            Mock::given(method("GET"))
+                .and(path("/api/v1/institutional/holdings"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "items": [holding("1"), holding("2")],
+                    "nextCursor": "page2",
+                    "hasMore": true
+                })))
+                .mount(&mock_server)
+                .await;
+        });
+
+        let client = setup_client(&mock_server);
+
+        let first_two: Vec<_> = client
+            .institutional()
+            .iter(ListInstitutionalParams::default())
+            .take(2)
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(first_two.len(), 2);
+        let requests = runtime.block_on(mock_server.received_requests()).unwrap();
+        assert_eq!(requests.len(), 1);
+
+        let all: Vec<_> = client
+            .institutional()
+            .iter(ListInstitutionalParams::default())
+            .collect::<Result<_>>()
+            .unwrap();
+        let cusips: Vec<_> = all.iter().map(|h| h.cusip.as_str()).collect();
+        assert_eq!(cusips, vec!["1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_iter_ends_after_error() {
+        let (runtime, mock_server) = start_server();
+        runtime.block_on(
+            Mock::given(method("GET"))
+                .and(path("/api/v1/filings"))
+                .respond_with(ResponseTemplate::new(401))
+                .expect(1)
+                .mount(&mock_server),
+        );
+
+        let client = setup_client(&mock_server);
+        let mut iter = client.filings().iter(ListFilingsParams::default());
+        assert!(matches!(iter.next(), Some(Err(Error::Authentication))));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_iter_stops_on_repeated_cursor() {
+        let (runtime, mock_server) = start_server();
+        runtime.block_on(
+            Mock::given(method("GET"))
+                .and(path("/api/v1/institutional/holdings"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "items": [holding("1")],
+                    "nextCursor": "same",
+                    "hasMore": true
+                })))
+                .expect(2)
+                .mount(&mock_server),
+        );
+
+        let client = setup_client(&mock_server);
+        let results: Vec<_> = client
+            .institutional()
+            .iter(ListInstitutionalParams::default())
+            .collect();
+        assert_eq!(results.len(), 3);
+        assert!(results[..2].iter().all(|result| result.is_ok()));
+        assert!(matches!(results[2], Err(Error::RepeatedCursor { .. })));
+    }
+}
@@ -0,0 +1,470 @@
+//! Parallel bulk fetching over date ranges.
+//!
+//! This module provides [`BulkFetch`], which splits the date range of a
+//! list request into windows, paginates them concurrently, and merges the
+//! results into one ordered stream.
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+use async_stream::try_stream;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use serde::Serialize;
+
+use crate::client::EarningsFeed;
+use crate::error::{Error, Result};
+use crate::models::{Filing, InsiderTransaction, ListFilingsParams, ListInsiderParams};
+use crate::options::RequestOptions;
+use crate::pagination::{Paginated, Paginator};
+
+/// Default number of windows paginated concurrently.
+pub const DEFAULT_BULK_CONCURRENCY: usize = 4;
+
+/// How a date range is split into windows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sharding {
+    /// One window per day.
+    Daily,
+    /// Windows of seven days.
+    Weekly,
+    /// Windows of the given number of days.
+    Days(u32),
+    /// The given inclusive `(start, end)` windows, instead of the date range
+    /// of the parameters. The windows must not overlap.
+    Custom(Vec<(NaiveDate, NaiveDate)>),
+}
+
+/// Parameters with a `startDate`/`endDate` range.
+pub(crate) trait DateRange: Paginated + Serialize + Clone + Send + Sync + 'static {
+    fn date_range(&self) -> (Option<NaiveDate>, Option<NaiveDate>);
+
+    fn set_date_range(&mut self, start: NaiveDate, end: NaiveDate);
+}
+
+impl DateRange for ListFilingsParams {
+    fn date_range(&self) -> (Option<NaiveDate>, Option<NaiveDate>) {
+        (self.start_date, self.end_date)
+    }
+
+    fn set_date_range(&mut self, start: NaiveDate, end: NaiveDate) {
+        self.start_date = Some(start);
+        self.end_date = Some(end);
+    }
+}
+
+impl DateRange for ListInsiderParams {
+    fn date_range(&self) -> (Option<NaiveDate>, Option<NaiveDate>) {
+        (self.start_date, self.end_date)
+    }
+
+    fn set_date_range(&mut self, start: NaiveDate, end: NaiveDate) {
+        self.start_date = Some(start);
+        self.end_date = Some(end);
+    }
+}
+
+/// Paginates one window.
+type PaginateWindow<'a, T> = Box<dyn Fn(NaiveDate, NaiveDate) -> Paginator<'a, T> + Send + 'a>;
+
+/// Fetches a date range as concurrently paginated windows.
+///
+/// Obtain one from `client.filings().bulk(params)` or
+/// `client.insider().bulk(params)`. The `start_date`/`end_date` range of
+/// the parameters is split into windows according to the [`Sharding`]
+/// (weekly by default), and up to [`concurrency`](Self::concurrency)
+/// windows are paginated at the same time, every request going through the
+/// client's rate limiter.
+///
+/// The stream yields the newest items first: windows are merged from the
+/// newest to the oldest, each sorted by `sorted_at` (filings) or
+/// `filed_at` (insider transactions). Items are yielded once, even if
+/// pages shift during pagination and return an item twice: filings are
+/// identified by accession number, and insider transactions by accession
+/// number together with the fields identifying a transaction within its
+/// filing.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use earningsfeed::{ListFilingsParams, Quarter, Sharding};
+///
+/// let params = ListFilingsParams::builder()
+///     .forms(vec!["8-K"])
+///     .calendar_quarter(2024, Quarter::Q1)
+///     .build();
+///
+/// let filings = client
+///     .filings()
+///     .bulk(params)
+///     .sharding(Sharding::Daily)
+///     .concurrency(8)
+///     .collect_all()
+///     .await?;
+/// ```
+pub struct BulkFetch<'a, T> {
+    range: (Option<NaiveDate>, Option<NaiveDate>),
+    paginate: PaginateWindow<'a, T>,
+    sort_key: fn(&T) -> DateTime<Utc>,
+    dedup_key: fn(&T) -> String,
+    sharding: Sharding,
+    concurrency: usize,
+}
+
+impl<'a, T: Send + 'static> BulkFetch<'a, T> {
+    /// Create a bulk fetch of the endpoint at `path`.
+    pub(crate) fn new<P: DateRange>(
+        client: &'a EarningsFeed,
+        path: &'static str,
+        params: P,
+        options: RequestOptions,
+        sort_key: fn(&T) -> DateTime<Utc>,
+        dedup_key: fn(&T) -> String,
+    ) -> Self
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        Self {
+            range: params.date_range(),
+            paginate: Box::new(move |start, end| {
+                let mut params = params.clone();
+                params.set_cursor(None);
+                params.set_date_range(start, end);
+                Paginator::new(client, path, params, options.clone())
+            }),
+            sort_key,
+            dedup_key,
+            sharding: Sharding::Weekly,
+            concurrency: DEFAULT_BULK_CONCURRENCY,
+        }
+    }
+
+    /// Set how the date range is split into windows.
+    ///
+    /// Defaults to [`Sharding::Weekly`].
+    #[must_use]
+    pub fn sharding(mut self, sharding: Sharding) -> Self {
+        self.sharding = sharding;
+        self
+    }
+
+    /// Set the maximum number of windows paginated at the same time.
+    ///
+    /// Defaults to 4. Values below 1 are treated as 1.
+    #[must_use]
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// The windows to fetch, newest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if the parameters lack a date range (unless
+    /// the windows are custom), a window starts after it ends, or custom
+    /// windows overlap.
+    pub fn windows(&self) -> Result<Vec<(NaiveDate, NaiveDate)>> {
+        let mut windows = match &self.sharding {
+            Sharding::Custom(windows) => windows.clone(),
+            Sharding::Daily => split(self.date_range()?, 1),
+            Sharding::Weekly => split(self.date_range()?, 7),
+            Sharding::Days(0) => {
+                return Err(Error::Config(
+                    "bulk fetch windows must be at least 1 day".into(),
+                ))
+            }
+            Sharding::Days(days) => split(self.date_range()?, *days),
+        };
+
+        if let Some((start, end)) = windows.iter().find(|(start, end)| start > end) {
+            return Err(Error::Config(format!(
+                "bulk fetch window starts after it ends: {} to {}",
+                start, end
+            )));
+        }
+        windows.sort_by_key(|&(start, end)| Reverse((end, start)));
+
+        // Items are ordered within each window, so windows must not overlap
+        // for the merged stream to be ordered.
+        if let Some(pair) = windows.windows(2).find(|pair| pair[1].1 >= pair[0].0) {
+            return Err(Error::Config(format!(
+                "bulk fetch windows overlap: {} to {} and {} to {}",
+                pair[1].0, pair[1].1, pair[0].0, pair[0].1
+            )));
+        }
+        Ok(windows)
+    }
+
+    fn date_range(&self) -> Result<(NaiveDate, NaiveDate)> {
+        let require = |name: &str, date: Option<NaiveDate>| {
+            date.ok_or_else(|| {
+                Error::Config(format!("bulk fetch requires {} in the parameters", name))
+            })
+        };
+        let start = require("start_date", self.range.0)?;
+        let end = require("end_date", self.range.1)?;
+        if start > end {
+            return Err(Error::Config(format!(
+                "bulk fetch start_date {} is after end_date {}",
+                start, end
+            )));
+        }
+        Ok((start, end))
+    }
+
+    /// Stream the items of every window, newest first.
+    ///
+    /// Windows are fetched in full before their items are yielded.
+    pub fn stream(self) -> impl Stream<Item = Result<T>> + 'a {
+        try_stream! {
+            let windows = self.windows()?;
+            let Self {
+                paginate,
+                sort_key,
+                dedup_key,
+                concurrency,
+                ..
+            } = self;
+
+            let mut results = stream::iter(windows)
+                .map(|(start, end)| paginate(start, end).collect_all())
+                .buffered(concurrency);
+
+            let mut seen = HashSet::new();
+            while let Some(mut items) = results.try_next().await? {
+                items.sort_by_key(|item| Reverse(sort_key(item)));
+                for item in items {
+                    if seen.insert(dedup_key(&item)) {
+                        yield item;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Collect the items of every window, newest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error encountered while fetching a window.
+    pub async fn collect_all(self) -> Result<Vec<T>> {
+        self.stream().try_collect().await
+    }
+}
+
+impl<T> std::fmt::Debug for BulkFetch<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BulkFetch")
+            .field("range", &self.range)
+            .field("sharding", &self.sharding)
+            .field("concurrency", &self.concurrency)
+            .finish()
+    }
+}
+
+/// Split an inclusive date range into windows of `days` days.
+///
+/// The last window is shorter if the range is not a multiple of `days`.
+fn split((start, end): (NaiveDate, NaiveDate), days: u32) -> Vec<(NaiveDate, NaiveDate)> {
+    let mut windows = Vec::new();
+    let mut window_start = start;
+    while window_start <= end {
+        let window_end = (window_start + Duration::days(i64::from(days) - 1)).min(end);
+        windows.push((window_start, window_end));
+        window_start = window_end + Duration::days(1);
+    }
+    windows
+}
+
+/// Sort key of filings.
+pub(crate) fn filing_sort_key(filing: &Filing) -> DateTime<Utc> {
+    filing.sorted_at
+}
+
+/// Identity of filings.
+pub(crate) fn filing_key(filing: &Filing) -> String {
+    filing.accession_number.clone()
+}
+
+/// Sort key of insider transactions.
+pub(crate) fn insider_sort_key(transaction: &InsiderTransaction) -> DateTime<Utc> {
+    transaction.filed_at
+}
+
+/// Identity of insider transactions.
+///
+/// A filing reports several transactions under one accession number.
+pub(crate) fn insider_key(t: &InsiderTransaction) -> String {
+    format!(
+        "{}|{}|{}|{}|{}|{}|{:?}|{:?}",
+        t.accession_number,
+        t.person_cik,
+        t.security_title,
+        t.transaction_date,
+        t.transaction_code,
+        t.is_derivative,
+        t.shares,
+        t.price_per_share
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn filing(accession_number: &str, sorted_at: &str) -> serde_json::Value {
+        serde_json::json!({
+            "accessionNumber": accession_number,
+            "cik": 320193,
+            "formType": "8-K",
+            "filedAt": sorted_at,
+            "provisional": false,
+            "sizeBytes": 1000,
+            "url": "https://www.sec.gov/",
+            "title": "Current report",
+            "status": "final",
+            "updatedAt": sorted_at,
+            "sortedAt": sorted_at
+        })
+    }
+
+    async fn mount_window(server: &MockServer, start: &str, end: &str, items: serde_json::Value) {
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .and(query_param("startDate", start))
+            .and(query_param("endDate", end))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": items,
+                "nextCursor": null,
+                "hasMore": false
+            })))
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    #[test]
+    fn test_split_windows() {
+        let range = (date("2024-01-01"), date("2024-01-10"));
+        assert_eq!(split(range, 1).len(), 10);
+        assert_eq!(
+            split(range, 7),
+            vec![
+                (date("2024-01-01"), date("2024-01-07")),
+                (date("2024-01-08"), date("2024-01-10")),
+            ]
+        );
+        assert_eq!(split((date("2024-01-02"), date("2024-01-01")), 7), vec![]);
+    }
+
+    #[tokio::test]
+    async fn test_windows_require_date_range() {
+        let client = EarningsFeed::new("test_key").unwrap();
+        let bulk = client.filings().bulk(ListFilingsParams::default());
+        match bulk.windows() {
+            Err(Error::Config(message)) => assert!(message.contains("start_date")),
+            other => panic!("expected config error, got {:?}", other),
+        }
+
+        let params = ListFilingsParams::builder()
+            .start_date(date("2024-02-01"))
+            .end_date(date("2024-01-31"))
+            .build();
+        assert!(client.filings().bulk(params).windows().is_err());
+
+        // Custom windows need no range, and are fetched newest first.
+        let custom = Sharding::Custom(vec![
+            (date("2024-01-01"), date("2024-01-15")),
+            (date("2024-02-01"), date("2024-02-15")),
+        ]);
+        let bulk = client.filings().bulk(ListFilingsParams::default());
+        let windows = bulk.sharding(custom).windows().unwrap();
+        assert_eq!(windows[0].0, date("2024-02-01"));
+
+        let overlapping = Sharding::Custom(vec![
+            (date("2024-01-01"), date("2024-01-15")),
+            (date("2024-01-15"), date("2024-01-31")),
+        ]);
+        let bulk = client.filings().bulk(ListFilingsParams::default());
+        let bulk = bulk.sharding(overlapping);
+        match bulk.windows() {
+            Err(Error::Config(message)) => assert!(message.contains("overlap")),
+            other => panic!("expected config error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bulk_merges_windows_newest_first() {
+        let mock_server = MockServer::start().await;
+
+        mount_window(
+            &mock_server,
+            "2024-01-08",
+            "2024-01-10",
+            serde_json::json!([
+                filing("0000000001-24-000003", "2024-01-09T14:00:00Z"),
+                filing("0000000001-24-000004", "2024-01-10T09:00:00Z"),
+                // Returned twice as pages shifted.
+                filing("0000000001-24-000003", "2024-01-09T14:00:00Z"),
+            ]),
+        )
+        .await;
+        mount_window(
+            &mock_server,
+            "2024-01-01",
+            "2024-01-07",
+            serde_json::json!([
+                filing("0000000001-24-000002", "2024-01-05T16:00:00Z"),
+                // Also returned by the newer window.
+                filing("0000000001-24-000003", "2024-01-09T14:00:00Z"),
+                filing("0000000001-24-000001", "2024-01-02T12:00:00Z"),
+            ]),
+        )
+        .await;
+
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .build()
+            .unwrap();
+        let client = EarningsFeed::with_config(config).unwrap();
+        let params = ListFilingsParams::builder()
+            .forms(vec!["8-K"])
+            .between(date("2024-01-01"), date("2024-01-10"))
+            .cursor("ignored")
+            .build();
+
+        let filings = client
+            .filings()
+            .bulk(params)
+            .concurrency(2)
+            .collect_all()
+            .await
+            .unwrap();
+        let accession_numbers: Vec<_> = filings
+            .iter()
+            .map(|f| f.accession_number.as_str())
+            .collect();
+        assert_eq!(
+            accession_numbers,
+            vec![
+                "0000000001-24-000004",
+                "0000000001-24-000003",
+                "0000000001-24-000002",
+                "0000000001-24-000001",
+            ]
+        );
+
+        let requests = mock_server.received_requests().await.unwrap();
+        assert!(requests
+            .iter()
+            .all(|r| r.url.query().is_some_and(|q| !q.contains("cursor"))));
+    }
+}
@@ -0,0 +1,647 @@
+//! In-memory response cache.
+//!
+//! This module provides [`CacheConfig`] for enabling the cache on a client
+//! and [`ResponseCache`] for inspecting and invalidating it at runtime.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::middleware::{HttpRequest, HttpResponse};
+
+/// Default maximum number of cached responses.
+pub const DEFAULT_CACHE_MAX_ENTRIES: usize = 1_000;
+
+/// Default lifetime of cached provisional filings.
+pub const DEFAULT_PROVISIONAL_TTL: Duration = Duration::from_secs(30);
+
+/// API resources whose cache lifetime can be configured independently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CacheResource {
+    /// SEC filings (`/api/v1/filings`).
+    Filings,
+    /// Insider transactions (`/api/v1/insider`).
+    Insider,
+    /// Institutional holdings (`/api/v1/institutional`).
+    Institutional,
+    /// Companies (`/api/v1/companies`).
+    Companies,
+}
+
+impl CacheResource {
+    /// Determine the resource an API path belongs to.
+    pub(crate) fn from_path(path: &str) -> Option<Self> {
+        let rest = path.strip_prefix("/api/v1/")?;
+        let segment = rest.split('/').next().unwrap_or_default();
+
+        match segment {
+            "filings" => Some(Self::Filings),
+            "insider" => Some(Self::Insider),
+            "institutional" => Some(Self::Institutional),
+            "companies" => Some(Self::Companies),
+            _ => None,
+        }
+    }
+
+    /// Whether an API path is a lookup of a single filing or company, as
+    /// opposed to a list or search page.
+    fn is_lookup(path: &str) -> bool {
+        let Some(rest) = path.strip_prefix("/api/v1/") else {
+            return false;
+        };
+        match rest.split('/').collect::<Vec<_>>()[..] {
+            ["filings", id] | ["companies", id] => !id.is_empty() && id != "search",
+            _ => false,
+        }
+    }
+}
+
+/// Configuration for the in-memory response cache.
+///
+/// Use [`CacheConfig::builder()`] to create a new configuration.
+///
+/// Successful lookups of a single filing or company are cached by path
+/// and query parameters. Each resource has its own time-to-live;
+/// resources without one, or with a zero TTL, are not cached. Final
+/// filings never change, so by default they are cached until evicted,
+/// while provisional filings expire after a short TTL. When the cache is
+/// full, the least recently used entry is evicted.
+///
+/// By default, company lookups are cached for one hour and filing lookups
+/// for five minutes. List and search pages change as new data arrives, so
+/// they are only cached once [`cache_lists`](CacheConfigBuilder::cache_lists)
+/// is enabled.
+///
+/// # Example
+///
+/// ```rust
+/// use earningsfeed::{CacheConfig, CacheResource, ClientConfig};
+/// use std::time::Duration;
+///
+/// let cache = CacheConfig::builder()
+///     .max_entries(10_000)
+///     .ttl(CacheResource::Companies, Duration::from_secs(24 * 60 * 60))
+///     .build();
+///
+/// let config = ClientConfig::builder()
+///     .api_key("your_api_key")
+///     .cache(cache)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheConfig {
+    /// Maximum number of cached responses.
+    pub max_entries: usize,
+    /// Time-to-live per resource. Resources without an entry are not cached.
+    pub ttls: HashMap<CacheResource, Duration>,
+    /// Time-to-live for provisional filings.
+    pub provisional_ttl: Duration,
+    /// Whether final filings are cached until evicted.
+    pub cache_final_filings: bool,
+    /// Whether list and search pages are cached, with the TTL of their
+    /// resource.
+    pub cache_lists: bool,
+}
+
+impl CacheConfig {
+    /// Create a new cache configuration builder.
+    #[must_use]
+    pub fn builder() -> CacheConfigBuilder {
+        CacheConfigBuilder::default()
+    }
+
+    /// Time-to-live for a successful response body for the given path.
+    ///
+    /// Returns `None` if the response should not be cached, and
+    /// `Some(None)` if it should be cached until evicted.
+    fn ttl_for(&self, path: &str, body: &[u8]) -> Option<Option<Duration>> {
+        let resource = CacheResource::from_path(path)?;
+        let ttl = self.ttl(resource, path)?;
+        if resource != CacheResource::Filings || !CacheResource::is_lookup(path) {
+            return Some(Some(ttl));
+        }
+
+        // Filing details report `provisional: false` once a filing is final.
+        let value: serde_json::Value = serde_json::from_slice(body).unwrap_or_default();
+        match value["provisional"].as_bool() {
+            Some(false) if self.cache_final_filings => Some(None),
+            Some(false) | None => Some(Some(ttl)),
+            Some(true) if self.provisional_ttl.is_zero() => None,
+            Some(true) => Some(Some(self.provisional_ttl)),
+        }
+    }
+
+    /// Time-to-live of the resource, if responses for the given path may
+    /// be cached.
+    fn ttl(&self, resource: CacheResource, path: &str) -> Option<Duration> {
+        if !self.cache_lists && !CacheResource::is_lookup(path) {
+            return None;
+        }
+        self.ttls
+            .get(&resource)
+            .copied()
+            .filter(|ttl| !ttl.is_zero())
+    }
+
+    /// Whether responses for the given path may be cached.
+    fn is_cacheable(&self, path: &str) -> bool {
+        CacheResource::from_path(path).is_some_and(|resource| self.ttl(resource, path).is_some())
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfigBuilder::default().build()
+    }
+}
+
+/// Builder for [`CacheConfig`].
+#[derive(Debug)]
+pub struct CacheConfigBuilder {
+    config: CacheConfig,
+}
+
+impl Default for CacheConfigBuilder {
+    fn default() -> Self {
+        Self {
+            config: CacheConfig {
+                max_entries: DEFAULT_CACHE_MAX_ENTRIES,
+                ttls: HashMap::from([
+                    (CacheResource::Filings, Duration::from_secs(5 * 60)),
+                    (CacheResource::Companies, Duration::from_secs(60 * 60)),
+                ]),
+                provisional_ttl: DEFAULT_PROVISIONAL_TTL,
+                cache_final_filings: true,
+                cache_lists: false,
+            },
+        }
+    }
+}
+
+impl CacheConfigBuilder {
+    /// Set the maximum number of cached responses.
+    ///
+    /// Defaults to 1000.
+    #[must_use]
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.config.max_entries = max_entries;
+        self
+    }
+
+    /// Set the time-to-live for a resource.
+    ///
+    /// A zero duration disables caching for the resource, including final
+    /// and provisional filings.
+    #[must_use]
+    pub fn ttl(mut self, resource: CacheResource, ttl: Duration) -> Self {
+        self.config.ttls.insert(resource, ttl);
+        self
+    }
+
+    /// Set the time-to-live for provisional filings.
+    ///
+    /// Defaults to 30 seconds. A zero duration disables caching of
+    /// provisional filings.
+    #[must_use]
+    pub fn provisional_ttl(mut self, ttl: Duration) -> Self {
+        self.config.provisional_ttl = ttl;
+        self
+    }
+
+    /// Whether final filings are cached until evicted.
+    ///
+    /// Enabled by default. When disabled, final filings use the
+    /// [`CacheResource::Filings`] TTL.
+    #[must_use]
+    pub fn cache_final_filings(mut self, enabled: bool) -> Self {
+        self.config.cache_final_filings = enabled;
+        self
+    }
+
+    /// Whether list and search pages are cached.
+    ///
+    /// Disabled by default. When enabled, pages use the TTL of their
+    /// resource, so a cached page may miss data published since it was
+    /// fetched.
+    #[must_use]
+    pub fn cache_lists(mut self, enabled: bool) -> Self {
+        self.config.cache_lists = enabled;
+        self
+    }
+
+    /// Build the cache configuration.
+    #[must_use]
+    pub fn build(self) -> CacheConfig {
+        self.config
+    }
+}
+
+/// Cache hit and miss counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups served from the cache.
+    pub hits: u64,
+    /// Lookups of cacheable resources that were not in the cache.
+    pub misses: u64,
+    /// Number of responses currently cached.
+    pub entries: usize,
+}
+
+/// In-memory cache of successful API responses.
+///
+/// Shared by all clones of a client. Obtain it via
+/// [`EarningsFeed::cache()`](crate::EarningsFeed::cache).
+pub struct ResponseCache {
+    config: CacheConfig,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+struct CacheEntry {
+    path: String,
+    response: HttpResponse,
+    expires_at: Option<Instant>,
+    last_used: u64,
+}
+
+impl ResponseCache {
+    /// Create an empty cache.
+    pub(crate) fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Look up a cached response for the given request.
+    pub(crate) fn get(&self, request: &HttpRequest) -> Option<HttpResponse> {
+        if !self.is_cacheable(request) {
+            return None;
+        }
+
+        let key = request.path_and_query();
+        let mut state = self.lock();
+        state.tick += 1;
+        let tick = state.tick;
+        let now = Instant::now();
+
+        let response = match state.entries.get_mut(&key) {
+            Some(entry) if entry.expires_at.map_or(true, |at| at > now) => {
+                entry.last_used = tick;
+                Some(entry.response.clone())
+            }
+            Some(_) => {
+                state.entries.remove(&key);
+                None
+            }
+            None => None,
+        };
+
+        match response {
+            Some(_) => state.hits += 1,
+            None => state.misses += 1,
+        }
+        response
+    }
+
+    /// Whether responses to the given request may be cached.
+    pub(crate) fn is_cacheable(&self, request: &HttpRequest) -> bool {
+        self.config.is_cacheable(&request.path)
+    }
+
+    /// Store a successful response for the given request.
+    pub(crate) fn insert(&self, request: &HttpRequest, response: &HttpResponse) {
+        let Some(ttl) = self.config.ttl_for(&request.path, &response.body) else {
+            return;
+        };
+        if self.config.max_entries == 0 {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut state = self.lock();
+        state.tick += 1;
+        let tick = state.tick;
+
+        let key = request.path_and_query();
+        if !state.entries.contains_key(&key) && state.entries.len() >= self.config.max_entries {
+            state
+                .entries
+                .retain(|_, entry| entry.expires_at.map_or(true, |at| at > now));
+            while state.entries.len() >= self.config.max_entries {
+                let oldest = state
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.last_used)
+                    .map(|(key, _)| key.clone());
+                match oldest {
+                    Some(oldest) => state.entries.remove(&oldest),
+                    None => break,
+                };
+            }
+        }
+
+        state.entries.insert(
+            key,
+            CacheEntry {
+                path: request.path.clone(),
+                response: response.clone(),
+                expires_at: ttl.map(|ttl| now + ttl),
+                last_used: tick,
+            },
+        );
+    }
+
+    /// Remove all cached responses for the given path, whatever their
+    /// query parameters.
+    ///
+    /// ```rust,ignore
+    /// if let Some(cache) = client.cache() {
+    ///     cache.invalidate("/api/v1/companies/320193");
+    /// }
+    /// ```
+    pub fn invalidate(&self, path: &str) {
+        self.lock().entries.retain(|_, entry| entry.path != path);
+    }
+
+    /// Remove all cached responses for the given resource.
+    pub fn invalidate_resource(&self, resource: CacheResource) {
+        self.lock()
+            .entries
+            .retain(|_, entry| CacheResource::from_path(&entry.path) != Some(resource));
+    }
+
+    /// Remove all cached responses.
+    ///
+    /// Hit and miss counters are not reset.
+    pub fn clear(&self) {
+        self.lock().entries.clear();
+    }
+
+    /// Get the current hit and miss counters.
+    #[must_use]
+    pub fn stats(&self) -> CacheStats {
+        let state = self.lock();
+        CacheStats {
+            hits: state.hits,
+            misses: state.misses,
+            entries: state.entries.len(),
+        }
+    }
+
+    /// Get the cache configuration.
+    #[must_use]
+    pub fn config(&self) -> &CacheConfig {
+        &self.config
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl std::fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseCache")
+            .field("config", &self.config)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(path: &str) -> HttpRequest {
+        HttpRequest::get(path, Vec::new())
+    }
+
+    fn json(value: serde_json::Value) -> HttpResponse {
+        HttpResponse::json(200, &value)
+    }
+
+    /// A filing detail body, as returned by `GET /api/v1/filings/{accession}`.
+    fn filing_detail(provisional: bool) -> HttpResponse {
+        let body = serde_json::json!({
+            "accessionNumber": "0000950170-24-000001",
+            "cik": 320193,
+            "formType": "10-K",
+            "filedAt": "2024-01-15T16:30:00Z",
+            "provisional": provisional,
+            "title": "Form 10-K",
+            "url": "https://www.sec.gov/...",
+            "sizeBytes": 12345,
+            "documents": [],
+            "roles": []
+        });
+        serde_json::from_value::<crate::models::FilingDetail>(body.clone()).unwrap();
+        json(body)
+    }
+
+    #[test]
+    fn test_resource_from_path() {
+        assert_eq!(
+            CacheResource::from_path("/api/v1/filings/0000950170-24-000001"),
+            Some(CacheResource::Filings)
+        );
+        assert_eq!(
+            CacheResource::from_path("/api/v1/insider/transactions"),
+            Some(CacheResource::Insider)
+        );
+        assert_eq!(
+            CacheResource::from_path("/api/v1/companies/search"),
+            Some(CacheResource::Companies)
+        );
+        assert_eq!(CacheResource::from_path("/api/v2/filings"), None);
+        assert_eq!(CacheResource::from_path("/health"), None);
+    }
+
+    #[test]
+    fn test_default_config() {
+        let config = CacheConfig::default();
+        assert_eq!(config.max_entries, DEFAULT_CACHE_MAX_ENTRIES);
+        assert!(config.is_cacheable("/api/v1/companies/320193"));
+        assert!(config.is_cacheable("/api/v1/filings/0000950170-24-000001"));
+        assert!(!config.is_cacheable("/api/v1/insider/transactions"));
+        assert!(config.cache_final_filings);
+        assert!(!config.cache_lists);
+    }
+
+    #[test]
+    fn test_lists_are_not_cached_by_default() {
+        let cache = ResponseCache::new(CacheConfig::default());
+        let list = request("/api/v1/filings");
+        let search = HttpRequest::get(
+            "/api/v1/companies/search",
+            vec![("q".to_string(), "apple".to_string())],
+        );
+        let page = json(serde_json::json!({ "items": [], "hasMore": false }));
+
+        cache.insert(&list, &page);
+        cache.insert(&search, &page);
+        assert!(cache.get(&list).is_none());
+        assert!(cache.get(&search).is_none());
+        assert_eq!(cache.stats(), CacheStats::default());
+
+        let cache = ResponseCache::new(CacheConfig::builder().cache_lists(true).build());
+        cache.insert(&list, &page);
+        assert!(cache.get(&list).is_some());
+    }
+
+    #[test]
+    fn test_zero_ttl_disables_final_filings() {
+        let config = CacheConfig::builder()
+            .ttl(CacheResource::Filings, Duration::ZERO)
+            .build();
+        let cache = ResponseCache::new(config);
+        let filing = request("/api/v1/filings/0000950170-24-000001");
+
+        cache.insert(&filing, &filing_detail(false));
+        assert!(cache.get(&filing).is_none());
+        assert_eq!(cache.stats(), CacheStats::default());
+    }
+
+    #[test]
+    fn test_hit_and_miss_counters() {
+        let cache = ResponseCache::new(CacheConfig::default());
+        let request = request("/api/v1/companies/320193");
+
+        assert!(cache.get(&request).is_none());
+        cache.insert(&request, &json(serde_json::json!({ "cik": 320193 })));
+        assert!(cache.get(&request).is_some());
+
+        let other = HttpRequest::get("/api/v1/companies/789019", Vec::new());
+        assert!(cache.get(&other).is_none());
+
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 1,
+                misses: 2,
+                entries: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_uncached_resource_is_not_counted() {
+        let cache = ResponseCache::new(CacheConfig::default());
+        let request = request("/api/v1/insider/transactions");
+
+        cache.insert(&request, &json(serde_json::json!({ "items": [] })));
+        assert!(cache.get(&request).is_none());
+        assert_eq!(cache.stats(), CacheStats::default());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_entries_expire_after_ttl() {
+        let config = CacheConfig::builder()
+            .ttl(CacheResource::Companies, Duration::from_secs(10))
+            .build();
+        let cache = ResponseCache::new(config);
+        let request = request("/api/v1/companies/320193");
+
+        cache.insert(&request, &json(serde_json::json!({ "cik": 320193 })));
+        tokio::time::advance(Duration::from_secs(9)).await;
+        assert!(cache.get(&request).is_some());
+        tokio::time::advance(Duration::from_secs(2)).await;
+        assert!(cache.get(&request).is_none());
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_final_filings_never_expire() {
+        let cache = ResponseCache::new(CacheConfig::default());
+        let final_filing = request("/api/v1/filings/final");
+        let provisional_filing = request("/api/v1/filings/provisional");
+
+        cache.insert(&final_filing, &filing_detail(false));
+        cache.insert(&provisional_filing, &filing_detail(true));
+
+        tokio::time::advance(DEFAULT_PROVISIONAL_TTL + Duration::from_secs(1)).await;
+        assert!(cache.get(&provisional_filing).is_none());
+
+        tokio::time::advance(Duration::from_secs(365 * 24 * 60 * 60)).await;
+        assert!(cache.get(&final_filing).is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_final_filings_use_ttl_when_not_cached_indefinitely() {
+        let config = CacheConfig::builder()
+            .ttl(CacheResource::Filings, Duration::from_secs(60))
+            .cache_final_filings(false)
+            .provisional_ttl(Duration::ZERO)
+            .build();
+        let cache = ResponseCache::new(config);
+        let final_filing = request("/api/v1/filings/final");
+        let provisional_filing = request("/api/v1/filings/provisional");
+
+        cache.insert(&final_filing, &filing_detail(false));
+        cache.insert(&provisional_filing, &filing_detail(true));
+        assert!(cache.get(&provisional_filing).is_none());
+        assert!(cache.get(&final_filing).is_some());
+
+        tokio::time::advance(Duration::from_secs(61)).await;
+        assert!(cache.get(&final_filing).is_none());
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let config = CacheConfig::builder().max_entries(2).build();
+        let cache = ResponseCache::new(config);
+        let (a, b, c) = (
+            request("/api/v1/companies/1"),
+            request("/api/v1/companies/2"),
+            request("/api/v1/companies/3"),
+        );
+        let body = json(serde_json::json!({}));
+
+        cache.insert(&a, &body);
+        cache.insert(&b, &body);
+        assert!(cache.get(&a).is_some());
+        cache.insert(&c, &body);
+
+        assert!(cache.get(&a).is_some());
+        assert!(cache.get(&b).is_none());
+        assert!(cache.get(&c).is_some());
+        assert_eq!(cache.stats().entries, 2);
+    }
+
+    #[test]
+    fn test_invalidation() {
+        let cache = ResponseCache::new(CacheConfig::builder().cache_lists(true).build());
+        let body = json(serde_json::json!({}));
+        let company = request("/api/v1/companies/320193");
+        let search = HttpRequest::get(
+            "/api/v1/companies/search",
+            vec![("q".to_string(), "apple".to_string())],
+        );
+        let filing = request("/api/v1/filings");
+
+        cache.insert(&company, &body);
+        cache.insert(&search, &body);
+        cache.insert(&filing, &body);
+
+        cache.invalidate("/api/v1/companies/search");
+        assert!(cache.get(&search).is_none());
+        assert!(cache.get(&company).is_some());
+
+        cache.invalidate_resource(CacheResource::Companies);
+        assert!(cache.get(&company).is_none());
+        assert!(cache.get(&filing).is_some());
+
+        cache.clear();
+        assert_eq!(cache.stats().entries, 0);
+    }
+}
@@ -0,0 +1,558 @@
+//! Recording and replay of API interactions.
+//!
+//! This module provides [`CassetteConfig`] for recording the requests sent
+//! by the client and their responses to a cassette file, and for serving
+//! them back later without network access, e.g. in CI.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+use futures::future::BoxFuture;
+use reqwest::header::HeaderValue;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::middleware::{self, HttpRequest, HttpResponse, Middleware, Next};
+
+/// Version of the cassette file format.
+const CASSETTE_VERSION: u32 = 1;
+
+/// Replacement for the API key in recorded interactions.
+const REDACTED: &str = "[REDACTED]";
+
+/// Response headers that are not recorded.
+///
+/// The body is recorded decompressed, so its encoding and length headers
+/// would not match it on replay.
+const SKIPPED_HEADERS: &[&str] = &[
+    "set-cookie",
+    "content-encoding",
+    "content-length",
+    "transfer-encoding",
+];
+
+/// Whether a cassette records or replays interactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send requests to the API and record them with their responses,
+    /// replacing the previous contents of the cassette.
+    Record,
+    /// Serve recorded responses without sending any request.
+    Replay,
+}
+
+/// Configuration for recording and replaying API interactions.
+///
+/// In record mode, every request sent by the client is written to the
+/// cassette file at `path` together with its response. Request headers,
+/// including the `Authorization` header, are never recorded, and the API
+/// key is replaced with `[REDACTED]` wherever it appears in the recorded
+/// query parameters and responses, so cassettes can be committed. This
+/// also covers keys set later with
+/// [`set_api_key`](crate::EarningsFeed::set_api_key) or refreshed by a
+/// credential provider.
+///
+/// In replay mode, no requests are sent. Each request is answered with the
+/// first unplayed recording of the same method, path and query parameters,
+/// so repeated requests replay their responses in recorded order (and the
+/// last one once all have been played). A request that was never recorded
+/// fails with [`Error::CassetteMiss`].
+///
+/// # Example
+///
+/// ```rust
+/// use earningsfeed::{CassetteConfig, ClientConfig};
+///
+/// let config = ClientConfig::builder()
+///     .api_key("your_api_key")
+///     .cassette(CassetteConfig::record("tests/cassettes/filings.json"))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CassetteConfig {
+    /// Path of the cassette file.
+    pub path: PathBuf,
+    /// Whether interactions are recorded or replayed.
+    pub mode: CassetteMode,
+}
+
+impl CassetteConfig {
+    /// Record interactions to the given cassette file.
+    #[must_use]
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            mode: CassetteMode::Record,
+        }
+    }
+
+    /// Replay interactions from the given cassette file.
+    #[must_use]
+    pub fn replay(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            mode: CassetteMode::Replay,
+        }
+    }
+}
+
+/// Contents of a cassette file.
+#[derive(Debug, Serialize, Deserialize)]
+struct CassetteFile {
+    version: u32,
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    path: String,
+    #[serde(default)]
+    query: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default)]
+    body: String,
+}
+
+impl RecordedResponse {
+    fn to_response(&self) -> HttpResponse {
+        self.headers
+            .iter()
+            .fold(HttpResponse::new(self.status), |response, (name, value)| {
+                response.with_header(name, value)
+            })
+            .with_body(self.body.clone())
+    }
+}
+
+struct CassetteState {
+    interactions: Vec<Interaction>,
+    played: Vec<bool>,
+    /// Number of interactions written to the cassette file.
+    saved: usize,
+    /// API keys used while recording, redacted from every interaction.
+    api_keys: Vec<String>,
+}
+
+/// Middleware implementing the cassette.
+///
+/// Runs last in the middleware chain, so it records the final request and
+/// the response as received from the API. The cassette file is written on
+/// the blocking thread pool.
+#[derive(Clone)]
+pub(crate) struct Cassette {
+    inner: Arc<CassetteInner>,
+}
+
+struct CassetteInner {
+    config: CassetteConfig,
+    /// The client's `Authorization` header, updated when the API key
+    /// changes.
+    authorization: Arc<RwLock<HeaderValue>>,
+    state: Mutex<CassetteState>,
+    /// Held while the cassette file is written.
+    writing: Mutex<()>,
+}
+
+impl Cassette {
+    /// Open the cassette for a client with the given `Authorization`
+    /// header.
+    ///
+    /// In record mode, the cassette file is replaced with an empty one. In
+    /// replay mode, it is loaded and must exist.
+    pub(crate) fn new(
+        config: CassetteConfig,
+        authorization: &Arc<RwLock<HeaderValue>>,
+    ) -> Result<Self> {
+        let interactions = match config.mode {
+            CassetteMode::Record => Vec::new(),
+            CassetteMode::Replay => load(&config.path)?,
+        };
+        let cassette = Self {
+            inner: Arc::new(CassetteInner {
+                config,
+                authorization: Arc::clone(authorization),
+                state: Mutex::new(CassetteState {
+                    played: vec![false; interactions.len()],
+                    saved: interactions.len(),
+                    interactions,
+                    api_keys: Vec::new(),
+                }),
+                writing: Mutex::new(()),
+            }),
+        };
+        cassette.lock().api_keys.extend(cassette.current_api_key());
+
+        if cassette.inner.config.mode == CassetteMode::Record {
+            if let Some(dir) = cassette.inner.config.path.parent() {
+                fs::create_dir_all(dir).map_err(|e| cassette.write_error(e))?;
+            }
+            cassette.save(Vec::new())?;
+        }
+        Ok(cassette)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CassetteState> {
+        self.inner.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The API key currently sent by the client.
+    fn current_api_key(&self) -> Option<String> {
+        let authorization = self
+            .inner
+            .authorization
+            .read()
+            .unwrap_or_else(|e| e.into_inner());
+        let key = authorization.to_str().ok()?.strip_prefix("Bearer ")?;
+        Some(key.to_string()).filter(|key| !key.is_empty())
+    }
+
+    /// Record an interaction, redacting every API key used so far.
+    fn record(&self, request: &HttpRequest, response: &HttpResponse) {
+        let current = self.current_api_key();
+        let mut state = self.lock();
+        if let Some(key) = current.filter(|key| !state.api_keys.contains(key)) {
+            state.api_keys.push(key);
+        }
+
+        let interaction = Interaction {
+            request: recorded_request(request, &state.api_keys),
+            response: recorded_response(response, &state.api_keys),
+        };
+        state.interactions.push(interaction);
+    }
+
+    /// Write the recorded interactions, unless a concurrent write already
+    /// covered them.
+    fn flush(&self) -> Result<()> {
+        let _writing = self.inner.writing.lock().unwrap_or_else(|e| e.into_inner());
+        let interactions = {
+            let state = self.lock();
+            if state.saved == state.interactions.len() {
+                return Ok(());
+            }
+            state.interactions.clone()
+        };
+
+        let count = interactions.len();
+        self.save(interactions)?;
+        self.lock().saved = count;
+        Ok(())
+    }
+
+    /// Write the cassette file.
+    fn save(&self, interactions: Vec<Interaction>) -> Result<()> {
+        let file = CassetteFile {
+            version: CASSETTE_VERSION,
+            interactions,
+        };
+        let mut contents = serde_json::to_vec_pretty(&file)?;
+        contents.push(b'\n');
+
+        // Write to a temporary file first so a crash never truncates the cassette.
+        let path = &self.inner.config.path;
+        let tmp = path.with_extension(format!("tmp{:08x}", fastrand::u32(..)));
+        fs::write(&tmp, &contents)
+            .and_then(|()| fs::rename(&tmp, path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&tmp);
+                self.write_error(e)
+            })
+    }
+
+    fn write_error(&self, error: std::io::Error) -> Error {
+        Error::Config(format!(
+            "cannot write cassette {}: {}",
+            self.inner.config.path.display(),
+            error
+        ))
+    }
+
+    /// Answer a request with its first unplayed recording.
+    fn replay(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        let api_keys: Vec<String> = self.current_api_key().into_iter().collect();
+        let recorded = recorded_request(request, &api_keys);
+        let mut state = self.lock();
+        let matching: Vec<usize> = (0..state.interactions.len())
+            .filter(|&i| state.interactions[i].request == recorded)
+            .collect();
+        let index = matching
+            .iter()
+            .find(|&&i| !state.played[i])
+            .or(matching.last())
+            .copied()
+            .ok_or_else(|| Error::CassetteMiss {
+                request: format!("{} {}", request.method, request.path_and_query()),
+            })?;
+
+        state.played[index] = true;
+        Ok(state.interactions[index].response.to_response())
+    }
+}
+
+/// Replace the API keys in a recorded value.
+fn redact(value: &str, api_keys: &[String]) -> String {
+    api_keys.iter().fold(value.to_string(), |value, key| {
+        value.replace(key.as_str(), REDACTED)
+    })
+}
+
+fn recorded_request(request: &HttpRequest, api_keys: &[String]) -> RecordedRequest {
+    RecordedRequest {
+        method: request.method.as_str().to_string(),
+        path: redact(&request.path, api_keys),
+        query: request
+            .query
+            .iter()
+            .map(|(name, value)| (name.clone(), redact(value, api_keys)))
+            .collect(),
+    }
+}
+
+fn recorded_response(response: &HttpResponse, api_keys: &[String]) -> RecordedResponse {
+    let headers = response
+        .headers
+        .iter()
+        .filter(|(name, _)| !SKIPPED_HEADERS.contains(&name.as_str()))
+        .filter_map(|(name, value)| {
+            let value = value.to_str().ok()?;
+            Some((name.as_str().to_string(), redact(value, api_keys)))
+        })
+        .collect();
+
+    RecordedResponse {
+        status: response.status,
+        headers,
+        body: redact(&String::from_utf8_lossy(&response.body), api_keys),
+    }
+}
+
+/// Load the interactions of a cassette file.
+fn load(path: &Path) -> Result<Vec<Interaction>> {
+    let contents = fs::read(path)
+        .map_err(|e| Error::Config(format!("cannot read cassette {}: {}", path.display(), e)))?;
+    let file: CassetteFile = serde_json::from_slice(&contents)
+        .map_err(|e| Error::Config(format!("invalid cassette {}: {}", path.display(), e)))?;
+    if file.version != CASSETTE_VERSION {
+        return Err(Error::Config(format!(
+            "unsupported cassette version {} in {}",
+            file.version,
+            path.display()
+        )));
+    }
+    Ok(file.interactions)
+}
+
+impl Middleware for Cassette {
+    fn handle<'a>(
+        &'a self,
+        request: HttpRequest,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<HttpResponse>> {
+        Box::pin(async move {
+            if self.inner.config.mode == CassetteMode::Replay {
+                return self.replay(&request);
+            }
+
+            let response = next.run(request.clone()).await?;
+            self.record(&request, &response);
+            let cassette = self.clone();
+            middleware::spawn_blocking(move || cassette.flush()).await??;
+            Ok(response)
+        })
+    }
+
+    fn name(&self) -> &str {
+        "Cassette"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::EarningsFeed;
+    use crate::models::ListFilingsParams;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const API_KEY: &str = "sk_live_cassette_secret";
+
+    fn client(base_url: &str, cassette: CassetteConfig) -> Result<EarningsFeed> {
+        EarningsFeed::builder()
+            .api_key(API_KEY)
+            .base_url(base_url)
+            .cassette(cassette)
+            .build()
+            .and_then(EarningsFeed::with_config)
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .and(query_param("ticker", "AAPL"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("X-Request-Id", "req_1")
+                    .set_body_json(serde_json::json!({
+                        "items": [],
+                        "nextCursor": null,
+                        "hasMore": false,
+                        "echo": API_KEY
+                    })),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/companies/1"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                "error": "Not Found",
+                "message": "Company not found"
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let cassette = dir.path().join("cassettes").join("session.json");
+        let params = ListFilingsParams::builder().ticker("AAPL").build();
+
+        let recorder = client(&mock_server.uri(), CassetteConfig::record(&cassette)).unwrap();
+        let page = recorder.filings().list(&params).await.unwrap();
+        assert!(page.items.is_empty());
+        assert!(matches!(
+            recorder.companies().get(1).await,
+            Err(Error::NotFound { .. })
+        ));
+
+        let contents = fs::read_to_string(&cassette).unwrap();
+        assert!(!contents.contains(API_KEY));
+        assert!(contents.contains(REDACTED));
+        assert!(contents.contains("req_1"));
+
+        // Nothing listens on the replay client's base URL.
+        let replayer = client("http://127.0.0.1:9", CassetteConfig::replay(&cassette)).unwrap();
+        let response = replayer.filings().list_with_meta(&params).await.unwrap();
+        assert!(response.data.items.is_empty());
+        assert_eq!(response.meta.request_id.as_deref(), Some("req_1"));
+        match replayer.companies().get(1).await {
+            Err(Error::NotFound { path, .. }) => assert_eq!(path, "/api/v1/companies/1"),
+            other => panic!("expected not found, got {:?}", other),
+        }
+
+        match replayer.companies().get(2).await {
+            Err(Error::CassetteMiss { request }) => {
+                assert_eq!(request, "GET /api/v1/companies/2");
+            }
+            other => panic!("expected cassette miss, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_redacts_keys_set_while_recording() {
+        let mock_server = MockServer::start().await;
+        const ROTATED: &str = "sk_live_cassette_rotated";
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/companies/320193"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("X-Echo", ROTATED)
+                    .set_body_json(serde_json::json!({ "echo": [API_KEY, ROTATED] })),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let cassette = dir.path().join("rotated.json");
+        let recorder = client(&mock_server.uri(), CassetteConfig::record(&cassette)).unwrap();
+        recorder.set_api_key(ROTATED).unwrap();
+        recorder
+            .get_with_meta::<serde_json::Value, ()>("/api/v1/companies/320193", None)
+            .await
+            .unwrap();
+
+        let contents = fs::read_to_string(&cassette).unwrap();
+        assert!(contents.contains(REDACTED));
+        assert!(!contents.contains(API_KEY));
+        assert!(!contents.contains(ROTATED));
+    }
+
+    #[tokio::test]
+    async fn test_replay_repeats_in_recorded_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let cassette = dir.path().join("retry.json");
+        let interaction = |status: u16, body: &str| Interaction {
+            request: RecordedRequest {
+                method: "GET".to_string(),
+                path: "/api/v1/filings".to_string(),
+                query: vec![("ticker".to_string(), "AAPL".to_string())],
+            },
+            response: RecordedResponse {
+                status,
+                headers: BTreeMap::from([(
+                    "content-type".to_string(),
+                    "application/json".to_string(),
+                )]),
+                body: body.to_string(),
+            },
+        };
+        let file = CassetteFile {
+            version: CASSETTE_VERSION,
+            interactions: vec![
+                interaction(401, r#"{"error":"Unauthorized"}"#),
+                interaction(200, r#"{"items":[],"nextCursor":"c2","hasMore":true}"#),
+            ],
+        };
+        fs::write(&cassette, serde_json::to_vec(&file).unwrap()).unwrap();
+
+        let client = client("http://127.0.0.1:9", CassetteConfig::replay(&cassette)).unwrap();
+        let params = ListFilingsParams::builder().ticker("AAPL").build();
+        assert!(matches!(
+            client.filings().list(&params).await,
+            Err(Error::Authentication)
+        ));
+        for _ in 0..2 {
+            let page = client.filings().list(&params).await.unwrap();
+            assert_eq!(page.next_cursor.as_deref(), Some("c2"));
+        }
+
+        let params = ListFilingsParams::builder().ticker("MSFT").build();
+        assert!(matches!(
+            client.filings().list(&params).await,
+            Err(Error::CassetteMiss { .. })
+        ));
+    }
+
+    #[test]
+    fn test_replay_requires_cassette() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = CassetteConfig::replay(dir.path().join("missing.json"));
+        let authorization = Arc::new(RwLock::new(HeaderValue::from_static("Bearer key")));
+        match Cassette::new(config, &authorization) {
+            Err(Error::Config(message)) => assert!(message.starts_with("cannot read cassette")),
+            Err(e) => panic!("expected config error, got {:?}", e),
+            Ok(_) => panic!("expected config error"),
+        }
+    }
+}
@@ -0,0 +1,536 @@
+//! Client-side circuit breaker.
+//!
+//! This module provides a circuit breaker that is shared by all clones of an
+//! [`EarningsFeed`](crate::EarningsFeed) client and fails requests fast while
+//! the API is unavailable.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::error::{Error, Result};
+use crate::middleware::HttpResponse;
+use crate::trace;
+
+/// Default number of consecutive failures that open the circuit.
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+
+/// Default time the circuit stays open before probe requests are let through.
+pub const DEFAULT_COOL_DOWN: Duration = Duration::from_secs(30);
+
+/// Callback invoked when the circuit changes state.
+pub type CircuitHook = Arc<dyn Fn(&CircuitEvent) + Send + Sync>;
+
+/// State of the circuit breaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests are sent normally.
+    Closed,
+    /// Requests fail fast with [`Error::CircuitOpen`].
+    Open,
+    /// A limited number of probe requests are sent to test recovery.
+    HalfOpen,
+}
+
+/// A change of the circuit breaker's state.
+///
+/// Passed to the hook registered with
+/// [`CircuitBreakerConfigBuilder::on_state_change`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitEvent {
+    /// The previous state.
+    pub from: CircuitState,
+    /// The new state.
+    pub to: CircuitState,
+    /// Consecutive failures observed when the change happened.
+    pub failures: u32,
+}
+
+/// Configuration for the circuit breaker.
+///
+/// Use [`CircuitBreakerConfig::builder()`] to create a new configuration.
+///
+/// The circuit opens after `failure_threshold` consecutive failures (5xx
+/// responses, timeouts and connection errors). While open, requests fail
+/// with [`Error::CircuitOpen`] without being sent. After `cool_down`, up to
+/// `half_open_probes` requests are let through: if they all succeed the
+/// circuit closes, and if any fails it opens again.
+///
+/// # Example
+///
+/// ```rust
+/// use earningsfeed::{CircuitBreakerConfig, ClientConfig};
+/// use std::time::Duration;
+///
+/// let breaker = CircuitBreakerConfig::builder()
+///     .failure_threshold(3)
+///     .cool_down(Duration::from_secs(10))
+///     .on_state_change(|event| eprintln!("circuit {:?} -> {:?}", event.from, event.to))
+///     .build();
+///
+/// let config = ClientConfig::builder()
+///     .api_key("your_api_key")
+///     .circuit_breaker(breaker)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures that open the circuit.
+    pub failure_threshold: u32,
+    /// Time the circuit stays open before probe requests are let through.
+    pub cool_down: Duration,
+    /// Probe requests that must succeed to close the circuit.
+    pub half_open_probes: u32,
+    /// Callback invoked when the circuit changes state.
+    pub on_state_change: Option<CircuitHook>,
+}
+
+impl CircuitBreakerConfig {
+    /// Create a new circuit breaker configuration builder.
+    #[must_use]
+    pub fn builder() -> CircuitBreakerConfigBuilder {
+        CircuitBreakerConfigBuilder::default()
+    }
+
+    /// Check that the failure threshold and half-open probes are at least 1.
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.failure_threshold == 0 {
+            return Err(Error::Config(
+                "circuit breaker failure threshold must be at least 1".into(),
+            ));
+        }
+        if self.half_open_probes == 0 {
+            return Err(Error::Config(
+                "circuit breaker half-open probes must be at least 1".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl std::fmt::Debug for CircuitBreakerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CircuitBreakerConfig")
+            .field("failure_threshold", &self.failure_threshold)
+            .field("cool_down", &self.cool_down)
+            .field("half_open_probes", &self.half_open_probes)
+            .field("on_state_change", &self.on_state_change.is_some())
+            .finish()
+    }
+}
+
+/// Builder for [`CircuitBreakerConfig`].
+pub struct CircuitBreakerConfigBuilder {
+    config: CircuitBreakerConfig,
+}
+
+impl Default for CircuitBreakerConfigBuilder {
+    fn default() -> Self {
+        Self {
+            config: CircuitBreakerConfig {
+                failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+                cool_down: DEFAULT_COOL_DOWN,
+                half_open_probes: 1,
+                on_state_change: None,
+            },
+        }
+    }
+}
+
+impl CircuitBreakerConfigBuilder {
+    /// Consecutive failures that open the circuit.
+    ///
+    /// Defaults to 5.
+    #[must_use]
+    pub fn failure_threshold(mut self, threshold: u32) -> Self {
+        self.config.failure_threshold = threshold;
+        self
+    }
+
+    /// Time the circuit stays open before probe requests are let through.
+    ///
+    /// Defaults to 30 seconds.
+    #[must_use]
+    pub fn cool_down(mut self, cool_down: Duration) -> Self {
+        self.config.cool_down = cool_down;
+        self
+    }
+
+    /// Probe requests that must succeed to close the circuit.
+    ///
+    /// Defaults to 1.
+    #[must_use]
+    pub fn half_open_probes(mut self, probes: u32) -> Self {
+        self.config.half_open_probes = probes;
+        self
+    }
+
+    /// Register a callback invoked when the circuit changes state.
+    ///
+    /// The callback runs on the task whose request caused the change.
+    #[must_use]
+    pub fn on_state_change<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&CircuitEvent) + Send + Sync + 'static,
+    {
+        self.config.on_state_change = Some(Arc::new(hook));
+        self
+    }
+
+    /// Build the circuit breaker configuration.
+    #[must_use]
+    pub fn build(self) -> CircuitBreakerConfig {
+        self.config
+    }
+}
+
+impl std::fmt::Debug for CircuitBreakerConfigBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CircuitBreakerConfigBuilder")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+/// Whether a request outcome counts as a failure of the API.
+pub(crate) fn is_failure(result: &Result<HttpResponse>) -> bool {
+    match result {
+        Ok(response) => response.status >= 500,
+        Err(error) => error.is_transport(),
+    }
+}
+
+/// Circuit breaker shared by all clones of a client.
+pub(crate) struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    state: Mutex<BreakerState>,
+}
+
+struct BreakerState {
+    state: CircuitState,
+    failures: u32,
+    open_until: Instant,
+    /// Probe requests in flight while half-open.
+    probes: u32,
+    /// Successful probe requests while half-open.
+    successes: u32,
+}
+
+impl BreakerState {
+    fn transition(&mut self, to: CircuitState, config: &CircuitBreakerConfig) -> CircuitEvent {
+        let event = CircuitEvent {
+            from: self.state,
+            to,
+            failures: self.failures,
+        };
+        self.state = to;
+        self.probes = 0;
+        self.successes = 0;
+        match to {
+            CircuitState::Open => self.open_until = Instant::now() + config.cool_down,
+            CircuitState::Closed => self.failures = 0,
+            CircuitState::HalfOpen => {}
+        }
+        event
+    }
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(BreakerState {
+                state: CircuitState::Closed,
+                failures: 0,
+                open_until: Instant::now(),
+                probes: 0,
+                successes: 0,
+            }),
+        }
+    }
+
+    /// The current state.
+    pub(crate) fn state(&self) -> CircuitState {
+        self.lock().state
+    }
+
+    /// Ask to send a request.
+    ///
+    /// Fails with [`Error::CircuitOpen`] if the circuit is open, or if it is
+    /// half-open and all probe requests have been let through.
+    pub(crate) fn acquire(&self) -> Result<CircuitPermit<'_>> {
+        let mut event = None;
+        let result = {
+            let mut state = self.lock();
+            if state.state == CircuitState::Open {
+                let now = Instant::now();
+                if now < state.open_until {
+                    return Err(Error::CircuitOpen {
+                        retry_after: Some(state.open_until - now),
+                    });
+                }
+                event = Some(state.transition(CircuitState::HalfOpen, &self.config));
+            }
+
+            match state.state {
+                CircuitState::HalfOpen
+                    if state.probes + state.successes >= self.config.half_open_probes =>
+                {
+                    Err(Error::CircuitOpen { retry_after: None })
+                }
+                CircuitState::HalfOpen => {
+                    state.probes += 1;
+                    Ok(CircuitPermit {
+                        breaker: self,
+                        probe: true,
+                        recorded: false,
+                    })
+                }
+                _ => Ok(CircuitPermit {
+                    breaker: self,
+                    probe: false,
+                    recorded: false,
+                }),
+            }
+        };
+        self.notify(event);
+        result
+    }
+
+    fn record(&self, probe: bool, failure: bool) {
+        let event = {
+            let mut state = self.lock();
+            if probe {
+                state.probes = state.probes.saturating_sub(1);
+            }
+            match state.state {
+                CircuitState::Closed if failure => {
+                    state.failures += 1;
+                    (state.failures >= self.config.failure_threshold)
+                        .then(|| state.transition(CircuitState::Open, &self.config))
+                }
+                CircuitState::Closed => {
+                    state.failures = 0;
+                    None
+                }
+                // Outcomes of requests admitted before the circuit opened
+                // are ignored.
+                CircuitState::HalfOpen if probe && failure => {
+                    state.failures += 1;
+                    Some(state.transition(CircuitState::Open, &self.config))
+                }
+                CircuitState::HalfOpen if probe => {
+                    state.successes += 1;
+                    (state.successes >= self.config.half_open_probes)
+                        .then(|| state.transition(CircuitState::Closed, &self.config))
+                }
+                _ => None,
+            }
+        };
+        self.notify(event);
+    }
+
+    /// Release a probe slot without recording an outcome.
+    fn release(&self) {
+        let mut state = self.lock();
+        if state.state == CircuitState::HalfOpen {
+            state.probes = state.probes.saturating_sub(1);
+        }
+    }
+
+    fn notify(&self, event: Option<CircuitEvent>) {
+        let Some(event) = event else {
+            return;
+        };
+        trace::circuit(&event);
+        if let Some(hook) = &self.config.on_state_change {
+            hook(&event);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl std::fmt::Debug for CircuitBreaker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("config", &self.config)
+            .field("state", &self.state())
+            .finish()
+    }
+}
+
+/// Permission to send a request, returned by [`CircuitBreaker::acquire`].
+///
+/// A probe permit dropped without recording an outcome, e.g. because the
+/// request was cancelled, frees its slot for another probe.
+pub(crate) struct CircuitPermit<'a> {
+    breaker: &'a CircuitBreaker,
+    probe: bool,
+    recorded: bool,
+}
+
+impl CircuitPermit<'_> {
+    /// Record the outcome of the request.
+    pub(crate) fn record(mut self, failure: bool) {
+        self.recorded = true;
+        self.breaker.record(self.probe, failure);
+    }
+}
+
+impl Drop for CircuitPermit<'_> {
+    fn drop(&mut self) {
+        if self.probe && !self.recorded {
+            self.breaker.release();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_rejects_invalid_config() {
+        let mut config = crate::ClientConfig::builder()
+            .api_key("test_key")
+            .build()
+            .unwrap();
+        config.circuit_breaker = Some(CircuitBreakerConfig::builder().half_open_probes(0).build());
+        assert!(matches!(
+            crate::EarningsFeed::with_config(config.clone()),
+            Err(Error::Config(_))
+        ));
+
+        config.circuit_breaker = Some(CircuitBreakerConfig::builder().failure_threshold(0).build());
+        assert!(crate::EarningsFeed::with_config(config).is_err());
+    }
+
+    fn breaker(threshold: u32, probes: u32) -> CircuitBreaker {
+        CircuitBreaker::new(
+            CircuitBreakerConfig::builder()
+                .failure_threshold(threshold)
+                .cool_down(Duration::from_secs(10))
+                .half_open_probes(probes)
+                .build(),
+        )
+    }
+
+    fn fail(breaker: &CircuitBreaker, times: u32) {
+        for _ in 0..times {
+            breaker.acquire().unwrap().record(true);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_opens_after_consecutive_failures() {
+        let breaker = breaker(3, 1);
+
+        fail(&breaker, 2);
+        breaker.acquire().unwrap().record(false);
+        fail(&breaker, 2);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        fail(&breaker, 1);
+        assert_eq!(breaker.state(), CircuitState::Open);
+        match breaker.acquire() {
+            Err(Error::CircuitOpen { retry_after }) => {
+                assert_eq!(retry_after, Some(Duration::from_secs(10)));
+            }
+            other => panic!("expected open circuit, got {:?}", other.err()),
+        };
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_half_open_probes_close_circuit() {
+        let breaker = breaker(1, 2);
+        fail(&breaker, 1);
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        let first = breaker.acquire().unwrap();
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        let second = breaker.acquire().unwrap();
+        assert!(matches!(
+            breaker.acquire(),
+            Err(Error::CircuitOpen { retry_after: None })
+        ));
+
+        first.record(false);
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        second.record(false);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_failed_probe_reopens_circuit() {
+        let breaker = breaker(1, 1);
+        fail(&breaker, 1);
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        breaker.acquire().unwrap().record(true);
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(breaker.acquire().is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_dropped_probe_frees_slot() {
+        let breaker = breaker(1, 1);
+        fail(&breaker, 1);
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        drop(breaker.acquire().unwrap());
+        breaker.acquire().unwrap().record(false);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_state_changes_are_reported() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let hook_events = events.clone();
+        let breaker = CircuitBreaker::new(
+            CircuitBreakerConfig::builder()
+                .failure_threshold(2)
+                .cool_down(Duration::from_secs(1))
+                .on_state_change(move |event| hook_events.lock().unwrap().push(*event))
+                .build(),
+        );
+
+        fail(&breaker, 2);
+        tokio::time::advance(Duration::from_secs(1)).await;
+        breaker.acquire().unwrap().record(false);
+
+        let transitions: Vec<_> = events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|event| (event.from, event.to))
+            .collect();
+        assert_eq!(
+            transitions,
+            vec![
+                (CircuitState::Closed, CircuitState::Open),
+                (CircuitState::Open, CircuitState::HalfOpen),
+                (CircuitState::HalfOpen, CircuitState::Closed),
+            ]
+        );
+        assert_eq!(events.lock().unwrap()[0].failures, 2);
+    }
+
+    #[test]
+    fn test_failure_classification() {
+        assert!(is_failure(&Ok(HttpResponse::new(503))));
+        assert!(!is_failure(&Ok(HttpResponse::new(404))));
+        assert!(!is_failure(&Ok(HttpResponse::new(200))));
+        assert!(!is_failure(&Err(Error::Authentication)));
+    }
+}
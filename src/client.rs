@@ -3,19 +3,47 @@
 //! This module provides the main [`EarningsFeed`] client for interacting
 //! with the EarningsFeed API.
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
+use futures::future::{BoxFuture, FutureExt, Shared, WeakShared};
 use reqwest::{header, Client};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
+use crate::cache::ResponseCache;
+use crate::circuit_breaker::{self, CircuitBreaker, CircuitState};
 use crate::config::{ClientConfig, DEFAULT_BASE_URL, DEFAULT_TIMEOUT};
-use crate::error::{Error, Result};
+use crate::credentials::{ApiKey, CredentialProvider};
+use crate::cassette::Cassette;
+use crate::disk_cache::DiskCache;
+use crate::error::{Error, ErrorContext, Result};
+use crate::metrics::{Metrics, MetricsSnapshot};
+use crate::middleware::{self, HttpRequest, HttpResponse, Middleware};
+use crate::options::RequestOptions;
+use crate::rate_limit::{header_value, RateLimiter};
 use crate::resources::{CompaniesResource, FilingsResource, InsiderResource, InstitutionalResource};
+use crate::response::{RateLimitInfo, Response, ResponseMeta, REQUEST_ID_HEADER};
+use crate::retry::{RetryEvent, RetryPolicy};
+use crate::trace;
 
 /// Version of this SDK (used in User-Agent header).
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// A request whose result can be shared by several callers.
+type SharedRequest = BoxFuture<'static, Result<Response<HttpResponse>>>;
+
+/// An in-flight request whose result is shared by all callers.
+type InFlight = Shared<SharedRequest>;
+
+/// A weak reference to an in-flight request, with a unique id.
+///
+/// The map of in-flight requests only holds weak references, so a request
+/// whose callers have all gone away is dropped rather than kept alive.
+type InFlightEntry = (u64, WeakShared<SharedRequest>);
+
 /// Client for the EarningsFeed API.
 ///
 /// The client is the main entry point for interacting with the EarningsFeed API.
@@ -43,7 +71,45 @@ pub struct EarningsFeed {
 
 struct ClientInner {
     http: Client,
+    headers: header::HeaderMap,
+    authorization: Arc<RwLock<header::HeaderValue>>,
+    credentials: Option<Arc<dyn CredentialProvider>>,
     base_url: String,
+    timeout: Duration,
+    retry: Option<RetryPolicy>,
+    retries: AtomicU64,
+    rate_limiter: Option<RateLimiter>,
+    circuit_breaker: Option<CircuitBreaker>,
+    last_rate_limit: Mutex<Option<RateLimitInfo>>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    cache: Option<ResponseCache>,
+    single_flight: bool,
+    in_flight: Mutex<HashMap<String, InFlightEntry>>,
+    next_flight_id: AtomicU64,
+    metrics: Option<Metrics>,
+}
+
+/// Removes a shared request from the in-flight map when it is dropped,
+/// whether it completed or all its callers went away.
+struct InFlightGuard {
+    client: EarningsFeed,
+    key: String,
+    id: u64,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let mut in_flight = self
+            .client
+            .inner
+            .in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        // A newer request for the same key may have replaced this one.
+        if matches!(in_flight.get(&self.key), Some((id, _)) if *id == self.id) {
+            in_flight.remove(&self.key);
+        }
+    }
 }
 
 impl EarningsFeed {
@@ -66,7 +132,7 @@ impl EarningsFeed {
     /// # Errors
     ///
     /// Returns an error if the API key is empty or if the HTTP client cannot be created.
-    pub fn new(api_key: impl Into<String>) -> Result<Self> {
+    pub fn new(api_key: impl Into<ApiKey>) -> Result<Self> {
         let config = ClientConfig::builder().api_key(api_key).build()?;
         Self::with_config(config)
     }
@@ -89,17 +155,20 @@ impl EarningsFeed {
     ///
     /// # Errors
     ///
-    /// Returns an error if the HTTP client cannot be created.
+    /// Returns an error if the configuration is invalid, the HTTP client
+    /// cannot be created, or the disk cache directory cannot be created.
     pub fn with_config(config: ClientConfig) -> Result<Self> {
-        let mut headers = header::HeaderMap::new();
+        // The fields of `ClientConfig` are public, so it may not have been
+        // validated by the builder.
+        if let Some(limiter) = &config.rate_limiter {
+            limiter.validate()?;
+        }
+        if let Some(breaker) = &config.circuit_breaker {
+            breaker.validate()?;
+        }
 
-        // Authorization header
-        let auth_value = format!("Bearer {}", config.api_key);
-        headers.insert(
-            header::AUTHORIZATION,
-            header::HeaderValue::from_str(&auth_value)
-                .map_err(|_| Error::Config("invalid API key format".into()))?,
-        );
+        let authorization = Arc::new(RwLock::new(authorization(&config.api_key)?));
+        let mut headers = header::HeaderMap::new();
 
         // User-Agent header
         let user_agent = format!("earningsfeed-rust/{}", VERSION);
@@ -117,20 +186,67 @@ impl EarningsFeed {
 
         let timeout = config.timeout.unwrap_or(DEFAULT_TIMEOUT);
 
-        let http = Client::builder()
-            .default_headers(headers)
-            .timeout(timeout)
-            .build()?;
+        // Headers and timeout are applied per request, so they also apply
+        // to a pre-built client.
+        let http = config.transport.build_client()?;
 
         let base_url = config
             .base_url
             .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
 
+        let mut middleware = config.middleware;
+        if let Some(disk_cache) = config.disk_cache {
+            middleware.push(Arc::new(DiskCache::new(disk_cache, &base_url, &authorization)?));
+        }
+        if let Some(cassette) = config.cassette {
+            middleware.push(Arc::new(Cassette::new(cassette, &authorization)?));
+        }
+
         Ok(Self {
-            inner: Arc::new(ClientInner { http, base_url }),
+            inner: Arc::new(ClientInner {
+                http,
+                headers,
+                authorization,
+                credentials: config.credentials,
+                base_url,
+                timeout,
+                retry: config.retry,
+                retries: AtomicU64::new(0),
+                rate_limiter: config.rate_limiter.map(RateLimiter::new),
+                circuit_breaker: config.circuit_breaker.map(CircuitBreaker::new),
+                last_rate_limit: Mutex::new(None),
+                middleware,
+                cache: config.cache.map(ResponseCache::new),
+                single_flight: config.single_flight,
+                in_flight: Mutex::new(HashMap::new()),
+                next_flight_id: AtomicU64::new(0),
+                metrics: config.metrics.then(Metrics::default),
+            }),
         })
     }
 
+    /// Create a new client configured from environment variables.
+    ///
+    /// Reads `EARNINGSFEED_API_KEY` and the other `EARNINGSFEED_*`
+    /// variables, and the profile file named by `EARNINGSFEED_CONFIG`. See
+    /// [`ClientConfigBuilder::env`](crate::ClientConfigBuilder::env).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use earningsfeed::EarningsFeed;
+    ///
+    /// let client = EarningsFeed::from_env()?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a variable is malformed, the API key is not set,
+    /// or the HTTP client cannot be created.
+    pub fn from_env() -> Result<Self> {
+        Self::with_config(ClientConfig::from_env()?)
+    }
+
     /// Create a configuration builder.
     ///
     /// Convenience method for creating a new [`ClientConfig`] builder.
@@ -145,6 +261,107 @@ impl EarningsFeed {
         &self.inner.base_url
     }
 
+    /// Get the total number of retries performed by this client.
+    ///
+    /// The counter is shared by all clones of the client.
+    #[must_use]
+    pub fn retry_count(&self) -> u64 {
+        self.inner.retries.load(Ordering::Relaxed)
+    }
+
+    /// Get the most recently observed rate-limit state.
+    ///
+    /// Updated from the `X-RateLimit-*` headers of every response, including
+    /// error responses. Returns `None` until the server has reported one.
+    #[must_use]
+    pub fn rate_limit(&self) -> Option<RateLimitInfo> {
+        *self
+            .inner
+            .last_rate_limit
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Get the in-memory response cache, if enabled.
+    ///
+    /// The cache is shared by all clones of the client.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// if let Some(cache) = client.cache() {
+    ///     println!("cache hits: {}", cache.stats().hits);
+    ///     cache.invalidate("/api/v1/companies/320193");
+    /// }
+    /// ```
+    #[must_use]
+    pub fn cache(&self) -> Option<&ResponseCache> {
+        self.inner.cache.as_ref()
+    }
+
+    /// Get the state of the circuit breaker, if enabled.
+    ///
+    /// The breaker is shared by all clones of the client.
+    #[must_use]
+    pub fn circuit_state(&self) -> Option<CircuitState> {
+        self.inner.circuit_breaker.as_ref().map(CircuitBreaker::state)
+    }
+
+    /// Get a snapshot of the per-endpoint metrics, if enabled.
+    ///
+    /// Metrics are shared by all clones of the client and keep counting
+    /// after the snapshot is taken.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// if let Some(metrics) = client.metrics() {
+    ///     for (endpoint, m) in &metrics.endpoints {
+    ///         println!("{}: {} requests, {:.3} error rate", endpoint, m.requests, m.error_rate());
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    pub fn metrics(&self) -> Option<MetricsSnapshot> {
+        self.inner.metrics.as_ref().map(Metrics::snapshot)
+    }
+
+    /// Reset the per-endpoint metrics to zero, if enabled.
+    pub fn reset_metrics(&self) {
+        if let Some(metrics) = &self.inner.metrics {
+            metrics.reset();
+        }
+    }
+
+    /// Record a page fetched by a pagination stream.
+    pub(crate) fn record_page(&self, path: &str) {
+        if let Some(metrics) = &self.inner.metrics {
+            metrics.record_page(path);
+        }
+    }
+
+    /// Replace the API key used for subsequent requests.
+    ///
+    /// The key is swapped atomically for all clones of the client; requests
+    /// already sent keep the old key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is empty or not a valid header value.
+    pub fn set_api_key(&self, api_key: impl Into<ApiKey>) -> Result<()> {
+        let api_key = api_key.into();
+        if api_key.is_empty() {
+            return Err(Error::Config("API key cannot be empty".into()));
+        }
+        let value = authorization(&api_key)?;
+        *self
+            .inner
+            .authorization
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = value;
+        Ok(())
+    }
+
     /// Access the filings resource.
     ///
     /// # Example
@@ -195,9 +412,30 @@ impl EarningsFeed {
         CompaniesResource::new(self)
     }
 
-    /// Make a GET request to the API.
+    /// Make a GET request without per-request options.
+    #[cfg(test)]
+    pub(crate) async fn get_with_meta<T, P>(
+        &self,
+        path: &str,
+        params: Option<&P>,
+    ) -> Result<Response<T>>
+    where
+        T: DeserializeOwned,
+        P: Serialize,
+    {
+        self.get_with_options(path, params, &RequestOptions::default())
+            .await
+    }
+
+    /// Make a GET request to the API, returning response metadata.
     ///
     /// This is an internal method used by resource implementations.
+    /// Cached responses are returned without sending a request, and
+    /// concurrent identical requests share a single in-flight request.
+    /// Otherwise, each attempt passes through the configured [`Middleware`]
+    /// chain, and failed attempts are retried according to the configured
+    /// [`RetryPolicy`]. The whole request, including retries, fails with
+    /// [`Error::Cancelled`] if the options' cancellation token is cancelled.
     ///
     /// # Type Parameters
     ///
@@ -208,61 +446,384 @@ impl EarningsFeed {
     ///
     /// * `path` - The API path (e.g., "/api/v1/filings")
     /// * `params` - Optional query parameters
+    /// * `options` - Per-request timeout, headers and cancellation token
     ///
     /// # Errors
     ///
     /// Returns an error if the request fails or if the response cannot be parsed.
-    pub(crate) async fn get<T, P>(&self, path: &str, params: Option<&P>) -> Result<T>
+    pub(crate) async fn get_with_options<T, P>(
+        &self,
+        path: &str,
+        params: Option<&P>,
+        options: &RequestOptions,
+    ) -> Result<Response<T>>
+    where
+        T: DeserializeOwned,
+        P: Serialize,
+    {
+        options.run(self.get_inner(path, params, options)).await
+    }
+
+    async fn get_inner<T, P>(
+        &self,
+        path: &str,
+        params: Option<&P>,
+        options: &RequestOptions,
+    ) -> Result<Response<T>>
     where
         T: DeserializeOwned,
         P: Serialize,
     {
-        let url = format!("{}{}", self.inner.base_url, path);
+        let query = match params {
+            Some(params) => query_pairs(params)?,
+            None => Vec::new(),
+        };
+        let mut request = HttpRequest::get(path, query);
+        request.headers = options.all_headers();
+        request.timeout = options.timeout;
+
+        let cache = self.inner.cache.as_ref();
+        if let Some(cache) = cache.filter(|cache| cache.is_cacheable(&request)) {
+            let started = Instant::now();
+            let cached = cache.get(&request);
+            if let Some(metrics) = &self.inner.metrics {
+                metrics.record_cache(&request.path, cached.is_some());
+            }
+            if let Some(response) = cached {
+                let data = serde_json::from_slice(&response.body)?;
+                let latency = started.elapsed();
+                let mut meta =
+                    ResponseMeta::from_headers(response.status, &response.headers, latency);
+                meta.cached = true;
+                return Ok(Response { data, meta });
+            }
+        }
+
+        let Response {
+            data: response,
+            meta,
+        } = if self.inner.single_flight && options.is_shareable() {
+            self.execute_shared(request.clone()).await?
+        } else {
+            self.execute_traced(&request).await?
+        };
 
-        let mut request = self.inner.http.get(&url);
-        if let Some(p) = params {
-            request = request.query(p);
+        if let Some(cache) = &self.inner.cache {
+            cache.insert(&request, &response);
         }
 
-        let response = request.send().await?;
-        let status = response.status();
+        let data = serde_json::from_slice(&response.body)?;
+        Ok(Response { data, meta })
+    }
 
-        match status.as_u16() {
+    /// Send a request, sharing the result with concurrent callers making
+    /// the same request.
+    ///
+    /// The shared request removes itself from the in-flight map when it
+    /// completes, or when every caller waiting on it has been dropped (e.g.
+    /// cancelled), so later calls send a new request.
+    async fn execute_shared(&self, request: HttpRequest) -> Result<Response<HttpResponse>> {
+        let key = request.path_and_query();
+        let in_flight: InFlight = {
+            let mut in_flight = self
+                .inner
+                .in_flight
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            match in_flight.get(&key).and_then(|(_, shared)| shared.upgrade()) {
+                Some(shared) => shared,
+                None => {
+                    let id = self.inner.next_flight_id.fetch_add(1, Ordering::Relaxed);
+                    let guard = InFlightGuard {
+                        client: self.clone(),
+                        key: key.clone(),
+                        id,
+                    };
+                    let client = self.clone();
+                    let shared = async move {
+                        let _guard = guard;
+                        client.execute_traced(&request).await
+                    }
+                    .boxed()
+                    .shared();
+                    if let Some(weak) = shared.downgrade() {
+                        in_flight.insert(key, (id, weak));
+                    }
+                    shared
+                }
+            }
+        };
+        in_flight.await
+    }
+
+    /// Send a request inside a tracing span.
+    async fn execute_traced(&self, request: &HttpRequest) -> Result<Response<HttpResponse>> {
+        let span = trace::RequestSpan::new(request);
+        span.run(self.execute(request, &span)).await
+    }
+
+    /// Send a request, retrying failed attempts according to the retry policy.
+    async fn execute(
+        &self,
+        request: &HttpRequest,
+        span: &trace::RequestSpan,
+    ) -> Result<Response<HttpResponse>> {
+        let mut attempt = 1;
+        let mut refreshed = false;
+
+        loop {
+            span.record_attempt(attempt);
+            let sent = self.authorization();
+            let error = match self.send(request.clone(), span).await {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+
+            // A rejected key is refreshed once, without counting as a retry.
+            if matches!(error, Error::Authentication) && !refreshed {
+                refreshed = true;
+                if self.refresh_credentials(&sent).await? {
+                    continue;
+                }
+            }
+
+            let Some(policy) = &self.inner.retry else {
+                return Err(error);
+            };
+            let Some(delay) = policy.next_delay(attempt, &error) else {
+                return Err(error);
+            };
+
+            self.inner.retries.fetch_add(1, Ordering::Relaxed);
+            trace::retry(attempt, delay, &error);
+            if let Some(hook) = &policy.on_retry {
+                hook(&RetryEvent {
+                    attempt,
+                    delay,
+                    error: &error,
+                });
+            }
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// The current `Authorization` header value.
+    fn authorization(&self) -> header::HeaderValue {
+        self.inner
+            .authorization
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Swap in a new API key after `sent` was rejected.
+    ///
+    /// Returns whether the key changed, either by another request
+    /// refreshing it in the meantime or by the credential provider. The
+    /// provider may block, e.g. on a secret store, so it runs on the
+    /// blocking thread pool.
+    async fn refresh_credentials(&self, sent: &header::HeaderValue) -> Result<bool> {
+        let Some(provider) = self.inner.credentials.clone() else {
+            return Ok(false);
+        };
+        if self.authorization() != sent {
+            return Ok(true);
+        }
+
+        let api_key = match tokio::task::spawn_blocking(move || provider.refresh()).await {
+            Ok(api_key) => api_key?,
+            Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+            Err(_) => return Err(Error::Cancelled),
+        };
+        if api_key.is_empty() {
+            return Ok(false);
+        }
+        let value = authorization(&api_key)?;
+        let mut current = self
+            .inner
+            .authorization
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        if *current == value {
+            return Ok(false);
+        }
+        *current = value;
+        Ok(true)
+    }
+
+    /// Send a single request through the middleware chain and map the
+    /// response status to a result.
+    ///
+    /// Successful responses are returned undecoded.
+    async fn send(
+        &self,
+        request: HttpRequest,
+        span: &trace::RequestSpan,
+    ) -> Result<Response<HttpResponse>> {
+        let started = Instant::now();
+        let transport = |request| self.transport(request).boxed();
+        let response = middleware::run(&self.inner.middleware, request.clone(), &transport).await?;
+        let status = response.status;
+        span.record_response(status, response.body.len(), started.elapsed());
+
+        let rate_limit = RateLimitInfo::from_headers(&response.headers);
+        if let Some(info) = &rate_limit {
+            if let Some(limiter) = &self.inner.rate_limiter {
+                limiter.observe(info);
+            }
+            *self
+                .inner
+                .last_rate_limit
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()) = Some(*info);
+        }
+
+        let request_id = header_value::<String>(&response.headers, REQUEST_ID_HEADER);
+        match status {
             200..=299 => {
-                let body = response.json().await?;
-                Ok(body)
+                let meta = ResponseMeta::from_headers(status, &response.headers, started.elapsed());
+                Ok(Response {
+                    data: response,
+                    meta,
+                })
             }
             401 => Err(Error::Authentication),
-            404 => Err(Error::NotFound { path: path.into() }),
+            404 => Err(Error::NotFound {
+                path: request.path,
+                request_id,
+            }),
             429 => {
-                let reset_at = response
-                    .headers()
-                    .get("X-RateLimit-Reset")
-                    .and_then(|v| v.to_str().ok())
-                    .and_then(|v| v.parse().ok());
-                Err(Error::RateLimit { reset_at })
+                let reset_at = rate_limit.and_then(|info| info.reset_at);
+                let retry_after = header_value(&response.headers, "Retry-After")
+                    .map(Duration::from_secs);
+                Err(Error::RateLimit {
+                    reset_at,
+                    retry_after,
+                    request_id,
+                })
             }
             400 => {
-                let body: serde_json::Value = response.json().await.unwrap_or_default();
-                let message = body["error"]
-                    .as_str()
-                    .unwrap_or("Invalid request")
-                    .to_string();
-                Err(Error::Validation { message })
+                let parsed = ErrorContext::parse(&request.path, &request.query, &response.body);
+                Err(Error::Validation {
+                    message: parsed.message.unwrap_or_else(|| "Invalid request".into()),
+                    request_id,
+                    context: parsed.context,
+                })
             }
             _ => {
-                let body: serde_json::Value = response.json().await.unwrap_or_default();
+                let parsed = ErrorContext::parse(&request.path, &request.query, &response.body);
                 Err(Error::Api {
-                    status: status.as_u16(),
-                    message: body["error"]
-                        .as_str()
-                        .unwrap_or("Unknown error")
-                        .to_string(),
-                    code: body["code"].as_str().map(String::from),
+                    status,
+                    message: parsed.message.unwrap_or_else(|| "Unknown error".into()),
+                    code: parsed.code,
+                    request_id,
+                    context: parsed.context,
                 })
             }
         }
     }
+
+    /// Send a request over the network, unless the circuit breaker is open.
+    async fn transport(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let permit = match &self.inner.circuit_breaker {
+            Some(breaker) => Some(breaker.acquire()?),
+            None => None,
+        };
+        let result = self.send_http(request).await;
+        if let Some(permit) = permit {
+            permit.record(circuit_breaker::is_failure(&result));
+        }
+        result
+    }
+
+    /// Send a request over the network, recording its metrics.
+    async fn send_http(&self, request: HttpRequest) -> Result<HttpResponse> {
+        if let Some(limiter) = &self.inner.rate_limiter {
+            limiter.acquire().await;
+        }
+
+        let Some(metrics) = &self.inner.metrics else {
+            return self.send_reqwest(request).await;
+        };
+        let path = request.path.clone();
+        let started = Instant::now();
+        let result = self.send_reqwest(request).await;
+        match &result {
+            Ok(response) => {
+                metrics.record_response(
+                    &path,
+                    response.status,
+                    response.body.len(),
+                    started.elapsed(),
+                );
+            }
+            Err(_) => metrics.record_error(&path, started.elapsed()),
+        }
+        result
+    }
+
+    /// Send a request with the HTTP client.
+    async fn send_reqwest(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let url = format!("{}{}", self.inner.base_url, request.path);
+        let timeout = request.timeout.unwrap_or(self.inner.timeout);
+        let mut builder = self
+            .inner
+            .http
+            .request(request.method, &url)
+            .headers(self.inner.headers.clone())
+            .header(header::AUTHORIZATION, self.authorization())
+            .headers(request.headers)
+            .timeout(timeout);
+        if !request.query.is_empty() {
+            builder = builder.query(&request.query);
+        }
+
+        let response = builder
+            .send()
+            .await
+            .map_err(|e| Error::from_transport(e, timeout))?;
+        let status = response.status().as_u16();
+        let headers = response.headers().clone();
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| Error::from_transport(e, timeout))?;
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+/// Build the `Authorization` header value for an API key.
+fn authorization(api_key: &ApiKey) -> Result<header::HeaderValue> {
+    let mut value = header::HeaderValue::from_str(&format!("Bearer {}", api_key.expose_secret()))
+        .map_err(|_| Error::Config("invalid API key format".into()))?;
+    value.set_sensitive(true);
+    Ok(value)
+}
+
+/// Serialize query parameters into name/value pairs, sorted by name.
+///
+/// `None` fields are skipped by the parameter types themselves; any
+/// remaining `null` values are dropped.
+fn query_pairs<P: Serialize>(params: &P) -> Result<Vec<(String, String)>> {
+    let serde_json::Value::Object(map) = serde_json::to_value(params)? else {
+        return Ok(Vec::new());
+    };
+
+    Ok(map
+        .into_iter()
+        .filter_map(|(name, value)| match value {
+            serde_json::Value::Null => None,
+            serde_json::Value::String(value) => Some((name, value)),
+            value => Some((name, value.to_string())),
+        })
+        .collect())
 }
 
 impl std::fmt::Debug for EarningsFeed {
@@ -276,6 +837,10 @@ impl std::fmt::Debug for EarningsFeed {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cache::CacheConfig;
+    use crate::rate_limit::RateLimiterConfig;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
 
     #[test]
     fn test_new_with_valid_api_key() {
@@ -331,7 +896,7 @@ mod tests {
             .build()
             .unwrap();
 
-        assert_eq!(config.api_key, "test_key");
+        assert_eq!(config.api_key.expose_secret(), "test_key");
     }
 
     #[test]
@@ -339,4 +904,807 @@ mod tests {
         fn assert_send_sync<T: Send + Sync>() {}
         assert_send_sync::<EarningsFeed>();
     }
+
+    async fn setup_retry_client(mock_server: &MockServer, policy: RetryPolicy) -> EarningsFeed {
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .retry_policy(policy)
+            .build()
+            .unwrap();
+        EarningsFeed::with_config(config).unwrap()
+    }
+
+    fn fast_retry_policy() -> RetryPolicy {
+        RetryPolicy::builder()
+            .max_attempts(3)
+            .base_delay(Duration::from_millis(1))
+            .jitter(0.0)
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_retries_on_service_unavailable() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/companies/320193"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/companies/320193"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ok": true
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let attempts = Arc::new(AtomicU64::new(0));
+        let hook_attempts = attempts.clone();
+        let policy = RetryPolicy::builder()
+            .base_delay(Duration::from_millis(1))
+            .jitter(0.0)
+            .on_retry(move |event| {
+                assert!(matches!(event.error, Error::Api { status: 503, .. }));
+                hook_attempts.fetch_add(1, Ordering::Relaxed);
+            })
+            .build();
+
+        let client = setup_retry_client(&mock_server, policy).await;
+        let body: serde_json::Value = client
+            .get_with_meta::<_, ()>("/api/v1/companies/320193", None)
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(body["ok"], true);
+        assert_eq!(client.retry_count(), 2);
+        assert_eq!(attempts.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retries_give_up_after_max_attempts() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .respond_with(ResponseTemplate::new(502))
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+
+        let client = setup_retry_client(&mock_server, fast_retry_policy()).await;
+        let result = client
+            .get_with_meta::<serde_json::Value, ()>("/api/v1/filings", None)
+            .await;
+
+        assert!(matches!(result, Err(Error::Api { status: 502, .. })));
+        assert_eq!(client.retry_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_not_found() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings/invalid"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = setup_retry_client(&mock_server, fast_retry_policy()).await;
+        let result = client
+            .get_with_meta::<serde_json::Value, ()>("/api/v1/filings/invalid", None)
+            .await;
+
+        assert!(matches!(result, Err(Error::NotFound { .. })));
+        assert_eq!(client.retry_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_retries_rate_limit_after_reset() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .respond_with(ResponseTemplate::new(429).insert_header("X-RateLimit-Reset", "1"))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&mock_server)
+            .await;
+
+        let client = setup_retry_client(&mock_server, fast_retry_policy()).await;
+        let result = client
+            .get_with_meta::<serde_json::Value, ()>("/api/v1/filings", None)
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(client.retry_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_no_retries_without_policy() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .build()
+            .unwrap();
+        let client = EarningsFeed::with_config(config).unwrap();
+        let result = client
+            .get_with_meta::<serde_json::Value, ()>("/api/v1/filings", None)
+            .await;
+
+        assert!(matches!(result, Err(Error::Api { status: 503, .. })));
+        assert_eq!(client.retry_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_is_shared_across_clones() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .rate_limiter(RateLimiterConfig::new(20.0, 1))
+            .build()
+            .unwrap();
+        let client = EarningsFeed::with_config(config).unwrap();
+        let clones = [client.clone(), client.clone(), client];
+
+        let start = std::time::Instant::now();
+        for clone in &clones {
+            clone
+                .get_with_meta::<serde_json::Value, ()>("/api/v1/filings", None)
+                .await
+                .unwrap();
+        }
+
+        // One burst token, then two requests spaced 50ms apart.
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+
+    #[tokio::test]
+    async fn test_get_with_meta_and_rate_limit_state() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("X-Request-Id", "req_abc123")
+                    .insert_header("X-RateLimit-Limit", "100")
+                    .insert_header("X-RateLimit-Remaining", "99")
+                    .insert_header("X-RateLimit-Reset", "1703520000")
+                    .set_body_json(serde_json::json!([])),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .build()
+            .unwrap();
+        let client = EarningsFeed::with_config(config).unwrap();
+        assert!(client.rate_limit().is_none());
+
+        let response = client
+            .get_with_meta::<serde_json::Value, ()>("/api/v1/filings", None)
+            .await
+            .unwrap();
+
+        let expected = RateLimitInfo {
+            limit: Some(100),
+            remaining: Some(99),
+            reset_at: Some(1703520000),
+        };
+        assert_eq!(response.meta.status, 200);
+        assert_eq!(response.meta.request_id, Some("req_abc123".to_string()));
+        assert_eq!(response.meta.rate_limit, Some(expected));
+        assert_eq!(client.clone().rate_limit(), Some(expected));
+    }
+
+    #[tokio::test]
+    async fn test_cache_serves_repeated_lookups() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/companies/320193"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("X-Request-Id", "req_abc123")
+                    .set_body_json(serde_json::json!({ "cik": 320193 })),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .cache(CacheConfig::default())
+            .build()
+            .unwrap();
+        let client = EarningsFeed::with_config(config).unwrap();
+
+        let first = client
+            .get_with_meta::<serde_json::Value, ()>("/api/v1/companies/320193", None)
+            .await
+            .unwrap();
+        let second = client
+            .clone()
+            .get_with_meta::<serde_json::Value, ()>("/api/v1/companies/320193", None)
+            .await
+            .unwrap();
+
+        assert!(!first.meta.cached);
+        assert!(second.meta.cached);
+        assert_eq!(second.meta.request_id, Some("req_abc123".to_string()));
+        assert_eq!(second.data, first.data);
+
+        let stats = client.cache().unwrap().stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+    }
+
+    async fn concurrent_lookups(single_flight: bool, expected_requests: u64) {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/companies/320193"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_delay(Duration::from_millis(100))
+                    .set_body_json(serde_json::json!({ "cik": 320193 })),
+            )
+            .expect(expected_requests)
+            .mount(&mock_server)
+            .await;
+
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .single_flight(single_flight)
+            .build()
+            .unwrap();
+        let client = EarningsFeed::with_config(config).unwrap();
+
+        let lookups = (0..5).map(|_| {
+            let client = client.clone();
+            async move {
+                client
+                    .get_with_meta::<serde_json::Value, ()>("/api/v1/companies/320193", None)
+                    .await
+            }
+        });
+        let results = futures::future::join_all(lookups).await;
+
+        for result in results {
+            assert_eq!(result.unwrap().data["cik"], 320193);
+        }
+        assert!(client.inner.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_single_flight_coalesces_concurrent_requests() {
+        concurrent_lookups(true, 1).await;
+    }
+
+    #[tokio::test]
+    async fn test_single_flight_can_be_disabled() {
+        concurrent_lookups(false, 5).await;
+    }
+
+    #[tokio::test]
+    async fn test_single_flight_forgets_cancelled_requests() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/companies/320193"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_delay(Duration::from_millis(300))
+                    .set_body_json(serde_json::json!({ "cik": 320193 })),
+            )
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .timeout(Duration::from_secs(2))
+            .build()
+            .unwrap();
+        let client = EarningsFeed::with_config(config).unwrap();
+
+        let token = tokio_util::sync::CancellationToken::new();
+        let options = RequestOptions::new().cancellation_token(token.clone());
+        let leader = client.get_with_options::<serde_json::Value, ()>(
+            "/api/v1/companies/320193",
+            None,
+            &options,
+        );
+        let cancel = async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            token.cancel();
+        };
+        let (result, ()) = futures::join!(leader, cancel);
+        assert!(matches!(result, Err(Error::Cancelled)));
+        assert!(client.inner.in_flight.lock().unwrap().is_empty());
+        assert_eq!(Arc::strong_count(&client.inner), 1);
+
+        let response = client
+            .get_with_meta::<serde_json::Value, ()>("/api/v1/companies/320193", None)
+            .await
+            .unwrap();
+        assert_eq!(response.data["cik"], 320193);
+    }
+
+    #[tokio::test]
+    async fn test_single_flight_shares_errors() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings/invalid"))
+            .respond_with(ResponseTemplate::new(404).set_delay(Duration::from_millis(100)))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = setup_retry_client(&mock_server, fast_retry_policy()).await;
+        let (first, second) = futures::join!(
+            client.get_with_meta::<serde_json::Value, ()>("/api/v1/filings/invalid", None),
+            client.get_with_meta::<serde_json::Value, ()>("/api/v1/filings/invalid", None),
+        );
+
+        assert!(matches!(first, Err(Error::NotFound { .. })));
+        assert!(matches!(second, Err(Error::NotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_maps_to_timeout_error() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(500)))
+            .mount(&mock_server)
+            .await;
+
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let client = EarningsFeed::with_config(config).unwrap();
+        let err = client
+            .get_with_meta::<serde_json::Value, ()>("/api/v1/filings", None)
+            .await
+            .unwrap_err();
+
+        match &err {
+            Error::Timeout { timeout, source } => {
+                assert_eq!(*timeout, Duration::from_millis(50));
+                assert!(source.is_some());
+            }
+            other => panic!("expected timeout, got {:?}", other),
+        }
+        assert!(err.is_retryable());
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_custom_http_client_keeps_headers_and_timeout() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .and(header("Authorization", "Bearer test_key"))
+            .and(header("Accept", "application/json"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(500)))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .timeout(Duration::from_millis(50))
+            .http_client(reqwest::Client::new())
+            .build()
+            .unwrap();
+        let client = EarningsFeed::with_config(config).unwrap();
+        let err = client
+            .get_with_meta::<serde_json::Value, ()>("/api/v1/filings", None)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::Timeout { .. }), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_key_is_refreshed_from_provider() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/companies/320193"))
+            .and(header("Authorization", "Bearer old_key"))
+            .respond_with(ResponseTemplate::new(401))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/companies/320193"))
+            .and(header("Authorization", "Bearer new_key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let rotated = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let provider_rotated = rotated.clone();
+        let refresh_thread = Arc::new(Mutex::new(None));
+        let provider_refresh_thread = refresh_thread.clone();
+        let config = EarningsFeed::builder()
+            .credentials(move || {
+                let rotated = provider_rotated.swap(true, Ordering::Relaxed);
+                if rotated {
+                    *provider_refresh_thread.lock().unwrap() = Some(std::thread::current().id());
+                }
+                Ok(ApiKey::new(if rotated { "new_key" } else { "old_key" }))
+            })
+            .base_url(mock_server.uri())
+            .build()
+            .unwrap();
+        let client = EarningsFeed::with_config(config).unwrap();
+
+        for _ in 0..2 {
+            client
+                .get_with_meta::<serde_json::Value, ()>("/api/v1/companies/320193", None)
+                .await
+                .unwrap();
+        }
+        assert_eq!(client.retry_count(), 0);
+
+        // The provider is refreshed off the runtime's worker thread.
+        let refresh_thread = refresh_thread.lock().unwrap().unwrap();
+        assert_ne!(refresh_thread, std::thread::current().id());
+    }
+
+    #[tokio::test]
+    async fn test_set_api_key_rotates_for_clones() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/companies/320193"))
+            .and(header("Authorization", "Bearer rotated_key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = setup_retry_client(&mock_server, fast_retry_policy()).await;
+        let clone = client.clone();
+        client.set_api_key("rotated_key").unwrap();
+        assert!(client.set_api_key("").is_err());
+
+        clone
+            .get_with_meta::<serde_json::Value, ()>("/api/v1/companies/320193", None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_request_options_override_timeout_and_add_headers() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .and(header("X-Correlation-Id", "job-42"))
+            .and(header("X-Team", "research"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({}))
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let client = EarningsFeed::with_config(config).unwrap();
+        let options = RequestOptions::new()
+            .timeout(Duration::from_secs(5))
+            .header("X-Team", "research")
+            .correlation_id("job-42");
+
+        client
+            .get_with_options::<serde_json::Value, ()>("/api/v1/filings", None, &options)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cancellation_aborts_in_flight_request() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(10)))
+            .mount(&mock_server)
+            .await;
+
+        let client = setup_retry_client(&mock_server, fast_retry_policy()).await;
+        let token = tokio_util::sync::CancellationToken::new();
+        let options = RequestOptions::new().cancellation_token(token.clone());
+
+        let cancel = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            token.cancel();
+        });
+        let started = Instant::now();
+        let err = client
+            .get_with_options::<serde_json::Value, ()>("/api/v1/filings", None, &options)
+            .await
+            .unwrap_err();
+        cancel.await.unwrap();
+
+        assert!(matches!(err, Error::Cancelled), "{:?}", err);
+        assert!(!err.is_retryable());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_fails_fast_after_outage() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .retry_policy(fast_retry_policy())
+            .circuit_breaker(
+                crate::CircuitBreakerConfig::builder()
+                    .failure_threshold(2)
+                    .build(),
+            )
+            .build()
+            .unwrap();
+        let client = EarningsFeed::with_config(config).unwrap();
+        assert_eq!(client.circuit_state(), Some(CircuitState::Closed));
+
+        // The second attempt opens the circuit, so the third fails fast.
+        let err = client
+            .get_with_meta::<serde_json::Value, ()>("/api/v1/filings", None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::CircuitOpen { .. }), "{:?}", err);
+        assert!(!err.is_retryable());
+        assert!(err.retry_after().is_some());
+        assert_eq!(client.clone().circuit_state(), Some(CircuitState::Open));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_record_requests_pages_and_cache_hits() {
+        use futures::TryStreamExt;
+        use wiremock::matchers::query_param;
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/companies/320193"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"cik":320193}"#))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/companies/1"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .and(query_param("cursor", "page2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [], "nextCursor": null, "hasMore": false
+            })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [], "nextCursor": "page2", "hasMore": true
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .cache(CacheConfig::default())
+            .metrics(true)
+            .build()
+            .unwrap();
+        let client = EarningsFeed::with_config(config).unwrap();
+
+        for _ in 0..2 {
+            client
+                .get_with_meta::<serde_json::Value, ()>("/api/v1/companies/320193", None)
+                .await
+                .unwrap();
+        }
+        let result = client
+            .get_with_meta::<serde_json::Value, ()>("/api/v1/companies/1", None)
+            .await;
+        assert!(matches!(result, Err(Error::NotFound { .. })));
+        let filings: Vec<_> = client
+            .filings()
+            .iter(crate::ListFilingsParams::default())
+            .try_collect()
+            .await
+            .unwrap();
+        assert!(filings.is_empty());
+
+        let metrics = client.clone().metrics().unwrap();
+        let companies = metrics.endpoint("/api/v1/companies/{cik}").unwrap();
+        assert_eq!(companies.requests, 2);
+        assert_eq!(companies.errors, 1);
+        assert_eq!(companies.statuses.get(&404), Some(&1));
+        assert_eq!(companies.bytes_received, 14);
+        assert_eq!((companies.cache_hits, companies.cache_misses), (1, 2));
+        assert_eq!(companies.latency.count, 2);
+
+        let filings = metrics.endpoint("/api/v1/filings").unwrap();
+        assert_eq!((filings.requests, filings.pages), (2, 2));
+        // List pages are not cacheable, so they are not cache misses.
+        assert_eq!((filings.cache_hits, filings.cache_misses), (0, 0));
+        assert_eq!(filings.error_rate(), 0.0);
+
+        client.reset_metrics();
+        assert_eq!(client.metrics().unwrap().requests(), 0);
+        assert!(EarningsFeed::new("test_key").unwrap().metrics().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_connection_failure_maps_to_connect_error() {
+        // Nothing listens on the discard port.
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url("http://127.0.0.1:9")
+            .build()
+            .unwrap();
+        let client = EarningsFeed::with_config(config).unwrap();
+        let err = client
+            .get_with_meta::<serde_json::Value, ()>("/api/v1/filings", None)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::Connect { .. }), "{:?}", err);
+        assert!(err.is_retryable());
+        assert_eq!(err.status(), None);
+    }
+
+    #[tokio::test]
+    async fn test_status_errors_carry_request_id() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .insert_header("X-Request-Id", "req_limited")
+                    .insert_header("Retry-After", "12"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .build()
+            .unwrap();
+        let client = EarningsFeed::with_config(config).unwrap();
+        let err = client
+            .get_with_meta::<serde_json::Value, ()>("/api/v1/filings", None)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.status(), Some(429));
+        assert_eq!(err.request_id(), Some("req_limited"));
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(12)));
+    }
+
+    #[tokio::test]
+    async fn test_validation_error_keeps_details() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "error": "Invalid parameters",
+                "details": [{ "field": "limit", "reason": "must be between 1 and 100" }]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .build()
+            .unwrap();
+        let client = EarningsFeed::with_config(config).unwrap();
+        let params = crate::models::ListFilingsParams::builder().limit(500).build();
+        let err = client
+            .get_with_meta::<serde_json::Value, _>("/api/v1/filings", Some(&params))
+            .await
+            .unwrap_err();
+
+        let Error::Validation { message, context, .. } = err else {
+            panic!("expected validation error, got {:?}", err);
+        };
+        assert_eq!(message, "Invalid parameters");
+        assert_eq!(context.details[0].field.as_deref(), Some("limit"));
+        assert_eq!(context.path, "/api/v1/filings");
+        assert_eq!(
+            context.query,
+            vec![("limit".to_string(), "500".to_string())]
+        );
+        assert!(context.body.contains("must be between 1 and 100"));
+    }
+
+    #[test]
+    fn test_query_pairs_skips_unset_fields() {
+        let params = crate::models::ListFilingsParams::builder()
+            .ticker("AAPL")
+            .cik(320193)
+            .limit(10)
+            .build();
+
+        assert_eq!(
+            query_pairs(&params).unwrap(),
+            vec![
+                ("cik".to_string(), "320193".to_string()),
+                ("limit".to_string(), "10".to_string()),
+                ("ticker".to_string(), "AAPL".to_string()),
+            ]
+        );
+        assert!(query_pairs(&()).unwrap().is_empty());
+    }
 }
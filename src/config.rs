@@ -3,9 +3,20 @@
 //! This module provides the [`ClientConfig`] struct and its builder
 //! for configuring the HTTP client.
 
+use std::path::Path;
+use std::sync::Arc;
 use std::time::Duration;
 
+use crate::cache::CacheConfig;
+use crate::circuit_breaker::CircuitBreakerConfig;
+use crate::credentials::{ApiKey, CredentialProvider};
+use crate::cassette::CassetteConfig;
+use crate::disk_cache::DiskCacheConfig;
 use crate::error::{Error, Result};
+use crate::middleware::Middleware;
+use crate::rate_limit::RateLimiterConfig;
+use crate::retry::RetryPolicy;
+use crate::transport::TransportConfig;
 
 /// Default base URL for the EarningsFeed API.
 pub const DEFAULT_BASE_URL: &str = "https://earningsfeed.com";
@@ -31,12 +42,34 @@ pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
 /// ```
 #[derive(Debug, Clone)]
 pub struct ClientConfig {
-    /// API key for authentication.
-    pub api_key: String,
+    /// API key for authentication, redacted from `Debug` output.
+    pub api_key: ApiKey,
+    /// Source of replacement keys after a `401 Unauthorized` (disabled if `None`).
+    pub credentials: Option<Arc<dyn CredentialProvider>>,
     /// Base URL for API requests.
     pub base_url: Option<String>,
     /// Request timeout.
     pub timeout: Option<Duration>,
+    /// Retry policy for transient failures (disabled if `None`).
+    pub retry: Option<RetryPolicy>,
+    /// Client-side rate limiter (disabled if `None`).
+    pub rate_limiter: Option<RateLimiterConfig>,
+    /// Circuit breaker failing requests fast during outages (disabled if `None`).
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// Middleware run around every request, in order.
+    pub middleware: Vec<Arc<dyn Middleware>>,
+    /// In-memory response cache (disabled if `None`).
+    pub cache: Option<CacheConfig>,
+    /// Persistent on-disk HTTP cache (disabled if `None`).
+    pub disk_cache: Option<DiskCacheConfig>,
+    /// Recording or replay of API interactions (disabled if `None`).
+    pub cassette: Option<CassetteConfig>,
+    /// Whether concurrent identical requests share a single HTTP request.
+    pub single_flight: bool,
+    /// Whether per-endpoint metrics are recorded.
+    pub metrics: bool,
+    /// HTTP transport options.
+    pub transport: TransportConfig,
 }
 
 impl ClientConfig {
@@ -45,14 +78,50 @@ impl ClientConfig {
     pub fn builder() -> ClientConfigBuilder {
         ClientConfigBuilder::default()
     }
+
+    /// Load the configuration from environment variables.
+    ///
+    /// See [`ClientConfigBuilder::env`] for the variables that are read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a variable or the config file is malformed, or
+    /// if the resulting configuration is invalid.
+    pub fn from_env() -> Result<Self> {
+        Self::builder().env()?.build()
+    }
+
+    /// Load the configuration from a profile of a config file.
+    ///
+    /// See [`ClientConfigBuilder::profile`] for the file format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, the profile does not
+    /// exist or has malformed values, or the resulting configuration is
+    /// invalid.
+    pub fn from_profile(path: impl AsRef<Path>, profile: &str) -> Result<Self> {
+        Self::builder().profile(path, profile)?.build()
+    }
 }
 
 /// Builder for [`ClientConfig`].
 #[derive(Debug, Default)]
 pub struct ClientConfigBuilder {
-    api_key: Option<String>,
-    base_url: Option<String>,
-    timeout: Option<Duration>,
+    pub(crate) api_key: Option<ApiKey>,
+    pub(crate) credentials: Option<Arc<dyn CredentialProvider>>,
+    pub(crate) base_url: Option<String>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) retry: Option<RetryPolicy>,
+    pub(crate) rate_limiter: Option<RateLimiterConfig>,
+    pub(crate) circuit_breaker: Option<CircuitBreakerConfig>,
+    pub(crate) middleware: Vec<Arc<dyn Middleware>>,
+    pub(crate) cache: Option<CacheConfig>,
+    pub(crate) disk_cache: Option<DiskCacheConfig>,
+    pub(crate) cassette: Option<CassetteConfig>,
+    pub(crate) single_flight: Option<bool>,
+    pub(crate) metrics: bool,
+    pub(crate) transport: TransportConfig,
 }
 
 impl ClientConfigBuilder {
@@ -60,11 +129,23 @@ impl ClientConfigBuilder {
     ///
     /// This is required for authentication.
     #[must_use]
-    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+    pub fn api_key(mut self, api_key: impl Into<ApiKey>) -> Self {
         self.api_key = Some(api_key.into());
         self
     }
 
+    /// Load the API key from a credential provider.
+    ///
+    /// The initial key is read when the configuration is built, replacing
+    /// any key set with [`api_key`](Self::api_key). The client asks the
+    /// provider for a new key when the API rejects the current one. See
+    /// [`CredentialProvider`] for details.
+    #[must_use]
+    pub fn credentials(mut self, provider: impl CredentialProvider) -> Self {
+        self.credentials = Some(Arc::new(provider));
+        self
+    }
+
     /// Set the base URL for API requests.
     ///
     /// Defaults to `https://earningsfeed.com` if not specified.
@@ -83,24 +164,272 @@ impl ClientConfigBuilder {
         self
     }
 
+    /// Set the retry policy for transient failures.
+    ///
+    /// Retries are disabled if not specified.
+    #[must_use]
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Set the client-side rate limiter.
+    ///
+    /// The limiter is shared by all clones of the client. Disabled if not specified.
+    #[must_use]
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiterConfig) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Set the circuit breaker.
+    ///
+    /// The breaker is shared by all clones of the client. Disabled if not
+    /// specified. See [`CircuitBreakerConfig`] for details.
+    #[must_use]
+    pub fn circuit_breaker(mut self, circuit_breaker: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    /// Add a middleware to the request chain.
+    ///
+    /// Middleware run in the order they are added. See [`Middleware`] for details.
+    #[must_use]
+    pub fn middleware(mut self, middleware: impl Middleware) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Enable the in-memory response cache.
+    ///
+    /// The cache is shared by all clones of the client. Disabled if not specified.
+    #[must_use]
+    pub fn cache(mut self, cache: CacheConfig) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Enable the persistent on-disk HTTP cache.
+    ///
+    /// Disabled if not specified. See [`DiskCacheConfig`] for details.
+    #[must_use]
+    pub fn disk_cache(mut self, disk_cache: DiskCacheConfig) -> Self {
+        self.disk_cache = Some(disk_cache);
+        self
+    }
+
+    /// Record API interactions to a cassette file, or replay them from one.
+    ///
+    /// Disabled if not specified. See [`CassetteConfig`] for details.
+    #[must_use]
+    pub fn cassette(mut self, cassette: CassetteConfig) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Set whether concurrent identical requests share a single HTTP request.
+    ///
+    /// When enabled, callers requesting the same path and query parameters
+    /// while a request is in flight wait for it and receive a copy of its
+    /// result. Enabled by default.
+    #[must_use]
+    pub fn single_flight(mut self, enabled: bool) -> Self {
+        self.single_flight = Some(enabled);
+        self
+    }
+
+    /// Set whether per-endpoint metrics are recorded.
+    ///
+    /// When enabled, the client counts requests, status codes, bytes
+    /// received, latencies, pages and cache hits per endpoint; read them
+    /// with [`EarningsFeed::metrics`](crate::EarningsFeed::metrics). With the
+    /// `metrics` feature, they are also emitted through the `metrics` crate
+    /// facade. Disabled by default.
+    #[must_use]
+    pub fn metrics(mut self, enabled: bool) -> Self {
+        self.metrics = enabled;
+        self
+    }
+
+    /// Send all requests through a proxy.
+    ///
+    /// HTTP, HTTPS and (with the `socks` feature) SOCKS5 proxies are
+    /// supported. If not specified, the `HTTP_PROXY`, `HTTPS_PROXY` and
+    /// `NO_PROXY` environment variables are honored.
+    #[must_use]
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.transport.proxy = Some(proxy);
+        self
+    }
+
+    /// Set the timeout for establishing connections.
+    ///
+    /// Only limited by the request timeout if not specified.
+    #[must_use]
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.transport.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set how long idle pooled connections are kept alive.
+    ///
+    /// Defaults to 90 seconds if not specified.
+    #[must_use]
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.transport.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum number of idle pooled connections per host.
+    ///
+    /// Unlimited if not specified.
+    #[must_use]
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.transport.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Set whether to request gzip-compressed responses.
+    ///
+    /// Requires the `gzip` feature, which enables compression by default.
+    #[must_use]
+    pub fn gzip(mut self, enabled: bool) -> Self {
+        self.transport.gzip = Some(enabled);
+        self
+    }
+
+    /// Set whether to request brotli-compressed responses.
+    ///
+    /// Requires the `brotli` feature, which enables compression by default.
+    #[must_use]
+    pub fn brotli(mut self, enabled: bool) -> Self {
+        self.transport.brotli = Some(enabled);
+        self
+    }
+
+    /// Use HTTP/2 without negotiating it first.
+    ///
+    /// Requires the `http2` feature.
+    #[must_use]
+    pub fn http2_prior_knowledge(mut self) -> Self {
+        self.transport.http2_prior_knowledge = true;
+        self
+    }
+
+    /// Trust an additional root certificate, e.g. of a TLS-intercepting proxy.
+    #[must_use]
+    pub fn add_root_certificate(mut self, certificate: reqwest::Certificate) -> Self {
+        self.transport.root_certificates.push(certificate);
+        self
+    }
+
+    /// Use a pre-built HTTP client instead of building one.
+    ///
+    /// The API key, user agent and timeout are still applied to every
+    /// request. Cannot be combined with the other transport options.
+    #[must_use]
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.transport.http_client = Some(client);
+        self
+    }
+
+    /// Apply settings from `EARNINGSFEED_*` environment variables.
+    ///
+    /// Every setting has a variable, e.g. `EARNINGSFEED_API_KEY`,
+    /// `EARNINGSFEED_TIMEOUT` or `EARNINGSFEED_RETRY_MAX_ATTEMPTS`. If
+    /// `EARNINGSFEED_CONFIG` names a config file, its profile selected by
+    /// `EARNINGSFEED_PROFILE` (or the file's `default_profile`) is applied
+    /// first, and variables override it. Middleware cannot be configured
+    /// this way.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] naming the variable if a value is malformed.
+    pub fn env(self) -> Result<Self> {
+        crate::profile::apply_env(self, |name| std::env::var(name).ok())
+    }
+
+    /// Apply the settings of a named profile from a config file.
+    ///
+    /// The file is JSON, or TOML with the `toml` feature if its extension
+    /// is `.toml`, and holds a `profiles` table of named profiles:
+    ///
+    /// ```toml
+    /// default_profile = "prod"
+    ///
+    /// [profiles.prod]
+    /// api_key = "your_api_key"
+    /// timeout = "30s"
+    /// retry = { max_attempts = 5, base_delay = "500ms" }
+    ///
+    /// [profiles.local]
+    /// api_key = "test"
+    /// base_url = "http://localhost:8080"
+    /// cache = { enabled = false }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if the file cannot be read, the profile
+    /// does not exist, or a setting is unknown or malformed.
+    pub fn profile(self, path: impl AsRef<Path>, profile: &str) -> Result<Self> {
+        crate::profile::apply_file(self, path.as_ref(), Some(profile))
+    }
+
     /// Build the configuration.
     ///
     /// # Errors
     ///
-    /// Returns an error if the API key is not set.
+    /// Returns an error if the API key is not set or cannot be read from the
+    /// credential provider, the rate limiter, circuit breaker or
+    /// cache configurations are invalid, or the transport options conflict
+    /// or require a disabled feature.
     pub fn build(self) -> Result<ClientConfig> {
-        let api_key = self
-            .api_key
-            .ok_or_else(|| Error::Config("API key is required".into()))?;
+        let api_key = match &self.credentials {
+            Some(provider) => provider.api_key()?,
+            None => self
+                .api_key
+                .ok_or_else(|| Error::Config("API key is required".into()))?,
+        };
 
         if api_key.is_empty() {
             return Err(Error::Config("API key cannot be empty".into()));
         }
 
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.validate()?;
+        }
+
+        if let Some(breaker) = &self.circuit_breaker {
+            breaker.validate()?;
+        }
+
+        if self.cache.as_ref().is_some_and(|cache| cache.max_entries == 0) {
+            return Err(Error::Config("cache max entries must be at least 1".into()));
+        }
+
+        if self.disk_cache.as_ref().is_some_and(|cache| cache.max_bytes == 0) {
+            return Err(Error::Config("disk cache max bytes must be positive".into()));
+        }
+
+        self.transport.validate()?;
+
         Ok(ClientConfig {
             api_key,
+            credentials: self.credentials,
             base_url: self.base_url,
             timeout: self.timeout,
+            retry: self.retry,
+            rate_limiter: self.rate_limiter,
+            circuit_breaker: self.circuit_breaker,
+            middleware: self.middleware,
+            cache: self.cache,
+            disk_cache: self.disk_cache,
+            cassette: self.cassette,
+            single_flight: self.single_flight.unwrap_or(true),
+            metrics: self.metrics,
+            transport: self.transport,
         })
     }
 }
@@ -116,9 +445,19 @@ mod tests {
             .build()
             .unwrap();
 
-        assert_eq!(config.api_key, "test_key");
+        assert_eq!(config.api_key.expose_secret(), "test_key");
         assert!(config.base_url.is_none());
         assert!(config.timeout.is_none());
+        assert!(config.retry.is_none());
+        assert!(config.rate_limiter.is_none());
+        assert!(config.circuit_breaker.is_none());
+        assert!(config.middleware.is_empty());
+        assert!(config.cache.is_none());
+        assert!(config.disk_cache.is_none());
+        assert!(config.cassette.is_none());
+        assert!(config.single_flight);
+        assert!(!config.metrics);
+        assert!(config.transport.http_client.is_none());
     }
 
     #[test]
@@ -130,7 +469,7 @@ mod tests {
             .build()
             .unwrap();
 
-        assert_eq!(config.api_key, "test_key");
+        assert_eq!(config.api_key.expose_secret(), "test_key");
         assert_eq!(
             config.base_url,
             Some("https://custom.example.com".to_string())
@@ -138,6 +477,106 @@ mod tests {
         assert_eq!(config.timeout, Some(Duration::from_secs(60)));
     }
 
+    #[test]
+    fn test_builder_with_retry_policy() {
+        let config = ClientConfig::builder()
+            .api_key("test_key")
+            .retry_policy(RetryPolicy::builder().max_attempts(5).build())
+            .build()
+            .unwrap();
+
+        assert_eq!(config.retry.unwrap().max_attempts, 5);
+    }
+
+    #[test]
+    fn test_builder_with_rate_limiter() {
+        let config = ClientConfig::builder()
+            .api_key("test_key")
+            .rate_limiter(RateLimiterConfig::new(10.0, 20))
+            .build()
+            .unwrap();
+
+        assert_eq!(config.rate_limiter, Some(RateLimiterConfig::new(10.0, 20)));
+    }
+
+    #[test]
+    fn test_builder_with_invalid_rate_limiter_fails() {
+        let err = ClientConfig::builder()
+            .api_key("test_key")
+            .rate_limiter(RateLimiterConfig::new(0.0, 20))
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "configuration error: rate limiter requests per second must be positive"
+        );
+
+        let err = ClientConfig::builder()
+            .api_key("test_key")
+            .rate_limiter(RateLimiterConfig::new(5.0, 0))
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "configuration error: rate limiter burst must be at least 1"
+        );
+    }
+
+    #[test]
+    fn test_builder_with_invalid_circuit_breaker_fails() {
+        let err = ClientConfig::builder()
+            .api_key("test_key")
+            .circuit_breaker(CircuitBreakerConfig::builder().failure_threshold(0).build())
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "configuration error: circuit breaker failure threshold must be at least 1"
+        );
+    }
+
+    #[test]
+    fn test_builder_with_cache() {
+        let config = ClientConfig::builder()
+            .api_key("test_key")
+            .cache(CacheConfig::builder().max_entries(10).build())
+            .build()
+            .unwrap();
+        assert_eq!(config.cache.unwrap().max_entries, 10);
+
+        let err = ClientConfig::builder()
+            .api_key("test_key")
+            .cache(CacheConfig::builder().max_entries(0).build())
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "configuration error: cache max entries must be at least 1"
+        );
+    }
+
+    #[test]
+    fn test_builder_with_disk_cache() {
+        let config = ClientConfig::builder()
+            .api_key("test_key")
+            .disk_cache(DiskCacheConfig::new("/tmp/earningsfeed").offline(true))
+            .build()
+            .unwrap();
+        let disk_cache = config.disk_cache.unwrap();
+        assert_eq!(disk_cache.dir, std::path::PathBuf::from("/tmp/earningsfeed"));
+        assert!(disk_cache.offline);
+
+        let err = ClientConfig::builder()
+            .api_key("test_key")
+            .disk_cache(DiskCacheConfig::new("/tmp/earningsfeed").max_bytes(0))
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "configuration error: disk cache max bytes must be positive"
+        );
+    }
+
     #[test]
     fn test_builder_without_api_key_fails() {
         let result = ClientConfig::builder().build();
@@ -168,7 +607,7 @@ mod tests {
             .build()
             .unwrap();
 
-        assert_eq!(config.api_key, "test_key");
+        assert_eq!(config.api_key.expose_secret(), "test_key");
     }
 
     #[test]
@@ -178,7 +617,7 @@ mod tests {
             .build()
             .unwrap();
 
-        assert_eq!(config.api_key, "test_key");
+        assert_eq!(config.api_key.expose_secret(), "test_key");
     }
 
     #[test]
@@ -207,6 +646,7 @@ mod tests {
 
         let debug_str = format!("{:?}", config);
         assert!(debug_str.contains("ClientConfig"));
-        assert!(debug_str.contains("test_key"));
+        assert!(debug_str.contains("ApiKey(<redacted>)"));
+        assert!(!debug_str.contains("test_key"));
     }
 }
@@ -0,0 +1,213 @@
+//! API key handling and credential providers.
+//!
+//! This module provides the [`ApiKey`] secret type, which never prints the
+//! key, and the [`CredentialProvider`] trait for loading keys from files,
+//! environment variables or rotating secret stores.
+
+use std::path::PathBuf;
+
+use crate::error::{Error, Result};
+
+/// An API key.
+///
+/// The key is redacted from `Debug` output; use
+/// [`expose_secret`](Self::expose_secret) to read it.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ApiKey(String);
+
+impl ApiKey {
+    /// Create a new API key.
+    #[must_use]
+    pub fn new(key: impl Into<String>) -> Self {
+        Self(key.into())
+    }
+
+    /// Get the key itself.
+    #[must_use]
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether the key is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl std::fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ApiKey(<redacted>)")
+    }
+}
+
+impl From<String> for ApiKey {
+    fn from(key: String) -> Self {
+        Self(key)
+    }
+}
+
+impl From<&str> for ApiKey {
+    fn from(key: &str) -> Self {
+        Self(key.to_string())
+    }
+}
+
+impl From<&String> for ApiKey {
+    fn from(key: &String) -> Self {
+        Self(key.clone())
+    }
+}
+
+/// A source of API keys.
+///
+/// Registered with
+/// [`ClientConfigBuilder::credentials`](crate::ClientConfigBuilder::credentials).
+/// The client asks for a key when it is created, and calls
+/// [`refresh`](Self::refresh) when the API rejects the current key with
+/// `401 Unauthorized`. If the refreshed key differs, the client swaps it in
+/// for all clones and retries the request once.
+///
+/// Closures returning `Result<ApiKey>` implement this trait, which suits
+/// keys cached from a secret store.
+///
+/// # Example
+///
+/// ```rust
+/// use earningsfeed::{ApiKey, ClientConfig, EnvCredentials};
+///
+/// std::env::set_var("RESEARCH_EARNINGSFEED_KEY", "your_api_key");
+///
+/// let config = ClientConfig::builder()
+///     .credentials(EnvCredentials::new("RESEARCH_EARNINGSFEED_KEY"))
+///     .build()
+///     .unwrap();
+/// assert_eq!(config.api_key, ApiKey::new("your_api_key"));
+/// ```
+pub trait CredentialProvider: Send + Sync + 'static {
+    /// Get the current API key.
+    fn api_key(&self) -> Result<ApiKey>;
+
+    /// Get a new API key after the current one was rejected.
+    ///
+    /// Called on tokio's blocking thread pool, so it may block on network
+    /// or file I/O. Defaults to reading the key again with
+    /// [`api_key`](Self::api_key).
+    fn refresh(&self) -> Result<ApiKey> {
+        self.api_key()
+    }
+}
+
+impl std::fmt::Debug for dyn CredentialProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CredentialProvider")
+    }
+}
+
+impl CredentialProvider for ApiKey {
+    fn api_key(&self) -> Result<ApiKey> {
+        Ok(self.clone())
+    }
+}
+
+impl<F> CredentialProvider for F
+where
+    F: Fn() -> Result<ApiKey> + Send + Sync + 'static,
+{
+    fn api_key(&self) -> Result<ApiKey> {
+        self()
+    }
+}
+
+/// Reads the API key from an environment variable.
+#[derive(Debug, Clone)]
+pub struct EnvCredentials {
+    var: String,
+}
+
+impl EnvCredentials {
+    /// Create a provider reading the given environment variable.
+    #[must_use]
+    pub fn new(var: impl Into<String>) -> Self {
+        Self { var: var.into() }
+    }
+}
+
+impl CredentialProvider for EnvCredentials {
+    fn api_key(&self) -> Result<ApiKey> {
+        std::env::var(&self.var)
+            .map(ApiKey::from)
+            .map_err(|_| Error::Config(format!("environment variable {} is not set", self.var)))
+    }
+}
+
+/// Reads the API key from a file, such as a mounted secret.
+///
+/// Surrounding whitespace is ignored. The file is read again on every
+/// refresh, so replacing it rotates the key.
+#[derive(Debug, Clone)]
+pub struct FileCredentials {
+    path: PathBuf,
+}
+
+impl FileCredentials {
+    /// Create a provider reading the given file.
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl CredentialProvider for FileCredentials {
+    fn api_key(&self) -> Result<ApiKey> {
+        std::fs::read_to_string(&self.path)
+            .map(|key| ApiKey::new(key.trim()))
+            .map_err(|e| {
+                Error::Config(format!(
+                    "cannot read API key from {}: {}",
+                    self.path.display(),
+                    e
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_key_is_redacted() {
+        let key = ApiKey::new("secret_key");
+        assert_eq!(format!("{:?}", key), "ApiKey(<redacted>)");
+        assert_eq!(key.expose_secret(), "secret_key");
+    }
+
+    #[test]
+    fn test_env_credentials() {
+        let provider = EnvCredentials::new("EARNINGSFEED_TEST_ENV_CREDENTIALS");
+        assert!(matches!(provider.api_key(), Err(Error::Config(_))));
+
+        std::env::set_var("EARNINGSFEED_TEST_ENV_CREDENTIALS", "env_key");
+        assert_eq!(provider.api_key().unwrap(), ApiKey::new("env_key"));
+    }
+
+    #[test]
+    fn test_file_credentials_reread_on_refresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("api_key");
+        std::fs::write(&path, "first_key\n").unwrap();
+
+        let provider = FileCredentials::new(&path);
+        assert_eq!(provider.api_key().unwrap(), ApiKey::new("first_key"));
+
+        std::fs::write(&path, "second_key\n").unwrap();
+        assert_eq!(provider.refresh().unwrap(), ApiKey::new("second_key"));
+    }
+
+    #[test]
+    fn test_closure_provider() {
+        let provider = || Ok(ApiKey::new("vault_key"));
+        assert_eq!(provider.api_key().unwrap(), ApiKey::new("vault_key"));
+    }
+}
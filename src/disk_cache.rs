@@ -0,0 +1,654 @@
+//! Persistent on-disk HTTP cache.
+//!
+//! This module provides [`DiskCacheConfig`] for enabling a disk cache that
+//! survives process restarts. Cached responses are revalidated with
+//! conditional requests (`If-None-Match` / `If-Modified-Since`), and a
+//! `304 Not Modified` response is served from the cache.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::middleware::{self, HttpRequest, HttpResponse, Middleware, Next};
+
+/// Default maximum size of the disk cache (256 MiB).
+pub const DEFAULT_DISK_CACHE_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
+/// File extension of cache entries.
+const ENTRY_EXTENSION: &str = "cache";
+
+/// Age after which temporary files of interrupted writes are removed.
+const STALE_TMP_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// Configuration for the persistent on-disk HTTP cache.
+///
+/// Successful responses are stored in `dir` together with their `ETag` and
+/// `Last-Modified` headers. Later requests with the same API key, base URL,
+/// path and query parameters are sent as conditional requests, and a
+/// `304 Not Modified` response is answered from the cache. When the cache
+/// grows beyond `max_bytes`, the oldest entries are removed.
+///
+/// In offline mode, no requests are sent: cached responses are returned
+/// as-is and anything else fails with [`Error::CacheMiss`].
+///
+/// # Example
+///
+/// ```rust
+/// use earningsfeed::{ClientConfig, DiskCacheConfig};
+///
+/// let config = ClientConfig::builder()
+///     .api_key("your_api_key")
+///     .disk_cache(DiskCacheConfig::new(".earningsfeed-cache").max_bytes(64 * 1024 * 1024))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiskCacheConfig {
+    /// Directory holding the cache entries. Created if it does not exist.
+    pub dir: PathBuf,
+    /// Maximum total size of the cache entries, in bytes.
+    pub max_bytes: u64,
+    /// Whether to serve requests from the cache only, without network access.
+    pub offline: bool,
+}
+
+impl DiskCacheConfig {
+    /// Create a new disk cache configuration for the given directory.
+    ///
+    /// The size cap defaults to 256 MiB.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes: DEFAULT_DISK_CACHE_MAX_BYTES,
+            offline: false,
+        }
+    }
+
+    /// Set the maximum total size of the cache entries, in bytes.
+    #[must_use]
+    pub fn max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Whether to serve requests from the cache only, without network access.
+    #[must_use]
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+}
+
+/// Metadata stored on the first line of a cache entry, followed by the body.
+#[derive(Debug, Serialize, Deserialize)]
+struct EntryMeta {
+    key: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    content_type: Option<String>,
+}
+
+struct Entry {
+    meta: EntryMeta,
+    body: Bytes,
+}
+
+/// Middleware implementing the disk cache.
+///
+/// Runs last in the middleware chain, so user middleware see the final
+/// request and the response as served from the cache. File system access
+/// runs on the blocking thread pool.
+#[derive(Clone)]
+pub(crate) struct DiskCache {
+    inner: Arc<DiskCacheInner>,
+}
+
+struct DiskCacheInner {
+    config: DiskCacheConfig,
+    /// Base URL of the client, part of every entry key.
+    base_url: String,
+    /// The client's `Authorization` header, whose hash is part of every
+    /// entry key so clients with different API keys do not share entries.
+    authorization: Arc<RwLock<HeaderValue>>,
+    /// Total size of the entries, updated on every store so the directory
+    /// is only scanned when the size cap is exceeded.
+    total_bytes: Mutex<u64>,
+}
+
+impl DiskCache {
+    /// Open the cache for a client with the given base URL and
+    /// `Authorization` header, creating its directory if needed.
+    ///
+    /// Temporary files left behind by interrupted writes are removed.
+    pub(crate) fn new(
+        config: DiskCacheConfig,
+        base_url: &str,
+        authorization: &Arc<RwLock<HeaderValue>>,
+    ) -> Result<Self> {
+        fs::create_dir_all(&config.dir).map_err(|e| {
+            Error::Config(format!(
+                "cannot create disk cache directory {}: {}",
+                config.dir.display(),
+                e
+            ))
+        })?;
+        let _ = remove_stale_tmp_files(&config.dir, STALE_TMP_AGE);
+        let total_bytes = list_entries(&config.dir)
+            .map(|entries| entries.iter().map(|(_, len, _)| len).sum())
+            .unwrap_or(0);
+        Ok(Self {
+            inner: Arc::new(DiskCacheInner {
+                config,
+                base_url: base_url.to_string(),
+                authorization: Arc::clone(authorization),
+                total_bytes: Mutex::new(total_bytes),
+            }),
+        })
+    }
+
+    fn key(&self, request: &HttpRequest) -> String {
+        let credentials = fnv1a(
+            self.inner
+                .authorization
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .as_bytes(),
+        );
+        format!(
+            "{:016x} {}{}",
+            credentials,
+            self.inner.base_url,
+            request.path_and_query()
+        )
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.inner.config.dir.join(format!(
+            "{:016x}.{}",
+            fnv1a(key.as_bytes()),
+            ENTRY_EXTENSION
+        ))
+    }
+
+    fn load(&self, key: &str) -> Option<Entry> {
+        let contents = fs::read(self.entry_path(key)).ok()?;
+        let newline = contents.iter().position(|&b| b == b'\n')?;
+        let meta: EntryMeta = serde_json::from_slice(&contents[..newline]).ok()?;
+        if meta.key != key {
+            return None;
+        }
+
+        let body = Bytes::from(contents).slice(newline + 1..);
+        Some(Entry { meta, body })
+    }
+
+    /// Write an entry, then enforce the size cap.
+    ///
+    /// Failures are ignored: the cache is best-effort.
+    fn store(&self, meta: &EntryMeta, body: &[u8]) {
+        let path = self.entry_path(&meta.key);
+        let Ok(mut contents) = serde_json::to_vec(meta) else {
+            return;
+        };
+        contents.push(b'\n');
+        contents.extend_from_slice(body);
+
+        let previous = fs::metadata(&path).map_or(0, |metadata| metadata.len());
+
+        // Write to a temporary file first so readers never see partial entries.
+        let tmp = path.with_extension(format!("tmp{:08x}", fastrand::u32(..)));
+        if fs::write(&tmp, &contents).is_err() || fs::rename(&tmp, &path).is_err() {
+            let _ = fs::remove_file(&tmp);
+            return;
+        }
+
+        let mut total = self
+            .inner
+            .total_bytes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        *total = (*total + contents.len() as u64).saturating_sub(previous);
+        if *total > self.inner.config.max_bytes {
+            if let Ok(remaining) = self.enforce_size_cap() {
+                *total = remaining;
+            }
+        }
+    }
+
+    /// Remove the oldest entries until the cache fits within `max_bytes`,
+    /// returning the remaining total size.
+    fn enforce_size_cap(&self) -> io::Result<u64> {
+        let mut entries = list_entries(&self.inner.config.dir)?;
+        let mut total = entries.iter().map(|(_, len, _)| len).sum();
+
+        entries.sort();
+        for (_, len, path) in entries {
+            if total <= self.inner.config.max_bytes {
+                break;
+            }
+            remove_entry(&path)?;
+            total -= len;
+        }
+        Ok(total)
+    }
+
+    /// Run `f` on the blocking thread pool.
+    async fn blocking<R: Send + 'static>(
+        &self,
+        f: impl FnOnce(&DiskCache) -> R + Send + 'static,
+    ) -> Result<R> {
+        let cache = self.clone();
+        middleware::spawn_blocking(move || f(&cache)).await
+    }
+}
+
+impl Middleware for DiskCache {
+    fn handle<'a>(
+        &'a self,
+        mut request: HttpRequest,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<HttpResponse>> {
+        Box::pin(async move {
+            let key = self.key(&request);
+            let entry = {
+                let key = key.clone();
+                self.blocking(move |cache| cache.load(&key)).await?
+            };
+
+            if self.inner.config.offline {
+                return match entry {
+                    Some(entry) => Ok(cached_response(entry)),
+                    None => Err(Error::CacheMiss {
+                        path: request.path_and_query(),
+                    }),
+                };
+            }
+
+            if let Some(entry) = &entry {
+                let validators = [
+                    (header::IF_NONE_MATCH, &entry.meta.etag),
+                    (header::IF_MODIFIED_SINCE, &entry.meta.last_modified),
+                ];
+                for (name, value) in validators {
+                    if let Some(value) =
+                        value.as_deref().and_then(|v| HeaderValue::from_str(v).ok())
+                    {
+                        request.headers.insert(name, value);
+                    }
+                }
+            }
+
+            let mut response = next.run(request).await?;
+            let (meta, body) = match (response.status, entry) {
+                (304, Some(entry)) => {
+                    let meta = EntryMeta {
+                        key,
+                        etag: header_string(&response.headers, header::ETAG).or(entry.meta.etag),
+                        last_modified: header_string(&response.headers, header::LAST_MODIFIED)
+                            .or(entry.meta.last_modified),
+                        content_type: entry.meta.content_type,
+                    };
+
+                    response.status = 200;
+                    response.body = entry.body.clone();
+                    if let Some(content_type) = &meta.content_type {
+                        if let Ok(value) = HeaderValue::from_str(content_type) {
+                            response.headers.insert(header::CONTENT_TYPE, value);
+                        }
+                    }
+                    // Rewriting the entry marks it as recently used.
+                    (meta, entry.body)
+                }
+                (200, _) => {
+                    let meta = EntryMeta {
+                        key,
+                        etag: header_string(&response.headers, header::ETAG),
+                        last_modified: header_string(&response.headers, header::LAST_MODIFIED),
+                        content_type: header_string(&response.headers, header::CONTENT_TYPE),
+                    };
+                    (meta, response.body.clone())
+                }
+                _ => return Ok(response),
+            };
+            self.blocking(move |cache| cache.store(&meta, &body))
+                .await?;
+
+            Ok(response)
+        })
+    }
+
+    fn name(&self) -> &str {
+        "DiskCache"
+    }
+}
+
+/// Build a response from a cache entry.
+fn cached_response(entry: Entry) -> HttpResponse {
+    let mut response = HttpResponse::new(200).with_body(entry.body);
+    let headers = [
+        (header::ETAG, entry.meta.etag),
+        (header::LAST_MODIFIED, entry.meta.last_modified),
+        (header::CONTENT_TYPE, entry.meta.content_type),
+    ];
+    for (name, value) in headers {
+        if let Some(value) = value.and_then(|v| HeaderValue::from_str(&v).ok()) {
+            response.headers.insert(name, value);
+        }
+    }
+    response
+}
+
+fn header_string(headers: &HeaderMap, name: header::HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from)
+}
+
+/// List the entries in `dir` with their modification time and size.
+fn list_entries(dir: &Path) -> io::Result<Vec<(SystemTime, u64, PathBuf)>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(ENTRY_EXTENSION) {
+            continue;
+        }
+        let metadata = entry.metadata()?;
+        entries.push((metadata.modified()?, metadata.len(), path));
+    }
+    Ok(entries)
+}
+
+/// Remove temporary files older than `max_age`.
+fn remove_stale_tmp_files(dir: &Path, max_age: Duration) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_tmp = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.starts_with("tmp"));
+        let age = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .map(|modified| modified.elapsed().unwrap_or_default());
+        if is_tmp && age.is_ok_and(|age| age >= max_age) {
+            remove_entry(&path)?;
+        }
+    }
+    Ok(())
+}
+
+fn remove_entry(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// 64-bit FNV-1a hash, used for stable entry file names.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::EarningsFeed;
+    use wiremock::matchers::{header as header_eq, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const HOLDINGS: &str = "/api/v1/institutional/holdings";
+
+    fn client(mock_server: &MockServer, config: DiskCacheConfig) -> EarningsFeed {
+        client_with_key(mock_server, config, "test_key")
+    }
+
+    fn client_with_key(
+        mock_server: &MockServer,
+        config: DiskCacheConfig,
+        api_key: &str,
+    ) -> EarningsFeed {
+        let config = EarningsFeed::builder()
+            .api_key(api_key)
+            .base_url(mock_server.uri())
+            .disk_cache(config)
+            .build()
+            .unwrap();
+        EarningsFeed::with_config(config).unwrap()
+    }
+
+    fn open(config: DiskCacheConfig) -> DiskCache {
+        let authorization = HeaderValue::from_static("Bearer test_key");
+        DiskCache::new(
+            config,
+            "http://localhost",
+            &Arc::new(RwLock::new(authorization)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[tokio::test]
+    async fn test_revalidates_with_etag() {
+        let mock_server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("GET"))
+            .and(path(HOLDINGS))
+            .and(header_eq("If-None-Match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304).insert_header("ETag", "\"v1\""))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path(HOLDINGS))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("ETag", "\"v1\"")
+                    .set_body_json(serde_json::json!({ "items": [1, 2, 3] })),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        // A fresh client on the same directory behaves like a restarted process.
+        let first = client(&mock_server, DiskCacheConfig::new(dir.path()))
+            .get_with_meta::<serde_json::Value, ()>(HOLDINGS, None)
+            .await
+            .unwrap();
+        let second = client(&mock_server, DiskCacheConfig::new(dir.path()))
+            .get_with_meta::<serde_json::Value, ()>(HOLDINGS, None)
+            .await
+            .unwrap();
+
+        assert_eq!(second.meta.status, 200);
+        assert_eq!(second.data, first.data);
+        assert_eq!(second.data["items"][2], 3);
+    }
+
+    #[tokio::test]
+    async fn test_offline_mode() {
+        let mock_server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("GET"))
+            .and(path(HOLDINGS))
+            .and(query_param("cik", "1067983"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "items": [] })),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let params = serde_json::json!({ "cik": 1067983 });
+        client(&mock_server, DiskCacheConfig::new(dir.path()))
+            .get_with_meta::<serde_json::Value, _>(HOLDINGS, Some(&params))
+            .await
+            .unwrap();
+
+        let offline = client(&mock_server, DiskCacheConfig::new(dir.path()).offline(true));
+        let cached = offline
+            .get_with_meta::<serde_json::Value, _>(HOLDINGS, Some(&params))
+            .await
+            .unwrap();
+        assert_eq!(cached.data["items"], serde_json::json!([]));
+
+        let result = offline
+            .get_with_meta::<serde_json::Value, _>(HOLDINGS, Some(&serde_json::json!({ "cik": 1 })))
+            .await;
+        match result {
+            Err(Error::CacheMiss { path }) => assert_eq!(path, format!("{}?cik=1", HOLDINGS)),
+            other => panic!("expected cache miss, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_size_cap_evicts_oldest_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = DiskCacheConfig::new(dir.path()).max_bytes(250);
+        let cache = open(config);
+        let body = vec![b'x'; 100];
+
+        for key in ["/a", "/b", "/c"] {
+            let meta = EntryMeta {
+                key: key.to_string(),
+                etag: None,
+                last_modified: None,
+                content_type: None,
+            };
+            cache.store(&meta, &body);
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+
+        assert!(cache.load("/a").is_none());
+        assert!(cache.load("/c").is_some());
+        let total: u64 = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().metadata().unwrap().len())
+            .sum();
+        assert!(total <= 250);
+        assert_eq!(*cache.inner.total_bytes.lock().unwrap(), total);
+    }
+
+    #[test]
+    fn test_total_bytes_tracks_rewrites_and_reopening() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = open(DiskCacheConfig::new(dir.path()));
+        let meta = EntryMeta {
+            key: "/a".to_string(),
+            etag: None,
+            last_modified: None,
+            content_type: None,
+        };
+
+        cache.store(&meta, &[b'x'; 100]);
+        cache.store(&meta, &[b'x'; 40]);
+        let total: u64 = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().metadata().unwrap().len())
+            .sum();
+        assert_eq!(*cache.inner.total_bytes.lock().unwrap(), total);
+
+        let reopened = open(DiskCacheConfig::new(dir.path()));
+        assert_eq!(*reopened.inner.total_bytes.lock().unwrap(), total);
+    }
+
+    #[tokio::test]
+    async fn test_entries_are_keyed_by_base_url() {
+        let staging = MockServer::start().await;
+        let production = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("GET"))
+            .and(path(HOLDINGS))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "items": [1] })),
+            )
+            .expect(1)
+            .mount(&staging)
+            .await;
+
+        client(&staging, DiskCacheConfig::new(dir.path()))
+            .get_with_meta::<serde_json::Value, ()>(HOLDINGS, None)
+            .await
+            .unwrap();
+
+        let offline = DiskCacheConfig::new(dir.path()).offline(true);
+        let cached = client(&staging, offline.clone())
+            .get_with_meta::<serde_json::Value, ()>(HOLDINGS, None)
+            .await
+            .unwrap();
+        assert_eq!(cached.data["items"][0], 1);
+
+        let result = client(&production, offline)
+            .get_with_meta::<serde_json::Value, ()>(HOLDINGS, None)
+            .await;
+        assert!(matches!(result, Err(Error::CacheMiss { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_entries_are_keyed_by_api_key() {
+        let mock_server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("GET"))
+            .and(path(HOLDINGS))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "items": [1] })),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        client_with_key(&mock_server, DiskCacheConfig::new(dir.path()), "key_a")
+            .get_with_meta::<serde_json::Value, ()>(HOLDINGS, None)
+            .await
+            .unwrap();
+
+        let offline = DiskCacheConfig::new(dir.path()).offline(true);
+        let result = client_with_key(&mock_server, offline.clone(), "key_b")
+            .get_with_meta::<serde_json::Value, ()>(HOLDINGS, None)
+            .await;
+        assert!(matches!(result, Err(Error::CacheMiss { .. })));
+
+        client_with_key(&mock_server, offline, "key_a")
+            .get_with_meta::<serde_json::Value, ()>(HOLDINGS, None)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_removes_stale_tmp_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let tmp = dir.path().join("0123456789abcdef.tmp0badf00d");
+        let entry = dir.path().join("0123456789abcdef.cache");
+        fs::write(&tmp, b"partial").unwrap();
+        fs::write(&entry, b"{}\n").unwrap();
+
+        // Files younger than the cutoff may belong to a concurrent write.
+        open(DiskCacheConfig::new(dir.path()));
+        assert!(tmp.exists());
+
+        remove_stale_tmp_files(dir.path(), Duration::ZERO).unwrap();
+        assert!(!tmp.exists());
+        assert!(entry.exists());
+    }
+}
@@ -3,14 +3,25 @@
 //! This module provides a comprehensive error hierarchy matching the
 //! Node.js and Python SDK error types.
 
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
 use thiserror::Error;
 
+use crate::retry::DEFAULT_RETRY_STATUSES;
+
+/// Maximum length of a message taken from a non-JSON error body.
+const MAX_TEXT_MESSAGE_LEN: usize = 200;
+
 /// Error types for the EarningsFeed client.
 ///
 /// These errors mirror the error types in the Node.js and Python SDKs
 /// for consistency across all official client libraries.
-#[derive(Error, Debug)]
+///
+/// Errors are cheap to clone, so a single failure can be shared by all
+/// callers of a deduplicated request.
+#[derive(Error, Debug, Clone)]
 pub enum Error {
     /// Authentication failed - API key is missing or invalid.
     ///
@@ -27,6 +38,10 @@ pub enum Error {
     RateLimit {
         /// Unix timestamp when rate limit resets.
         reset_at: Option<u64>,
+        /// Delay requested by the `Retry-After` header.
+        retry_after: Option<Duration>,
+        /// Server-assigned request ID.
+        request_id: Option<String>,
     },
 
     /// Requested resource was not found.
@@ -36,6 +51,8 @@ pub enum Error {
     NotFound {
         /// Path that was not found.
         path: String,
+        /// Server-assigned request ID.
+        request_id: Option<String>,
     },
 
     /// Request validation failed.
@@ -45,6 +62,10 @@ pub enum Error {
     Validation {
         /// Validation error message.
         message: String,
+        /// Server-assigned request ID.
+        request_id: Option<String>,
+        /// Raw response body, field-level details and the rejected request.
+        context: Box<ErrorContext>,
     },
 
     /// General API error.
@@ -58,23 +79,332 @@ pub enum Error {
         message: String,
         /// Error code from the API (e.g., "INTERNAL_ERROR").
         code: Option<String>,
+        /// Server-assigned request ID.
+        request_id: Option<String>,
+        /// Raw response body, field-level details and the rejected request.
+        context: Box<ErrorContext>,
     },
 
     /// Request timed out.
-    #[error("request timeout after {0:?}")]
-    Timeout(Duration),
+    ///
+    /// This error is returned when no complete response was received
+    /// within the configured timeout.
+    #[error("request timeout after {timeout:?}")]
+    Timeout {
+        /// The timeout that elapsed.
+        timeout: Duration,
+        /// The underlying transport error.
+        #[source]
+        source: Option<Arc<reqwest::Error>>,
+    },
+
+    /// Connection to the API could not be established.
+    ///
+    /// This covers DNS failures, refused connections and TLS handshake errors.
+    #[error("connection failed: {source}")]
+    Connect {
+        /// The underlying transport error.
+        #[source]
+        source: Arc<reqwest::Error>,
+    },
+
+    /// Response body could not be read or decoded.
+    #[error("failed to read response body: {source}")]
+    Decode {
+        /// The underlying transport error.
+        #[source]
+        source: Arc<reqwest::Error>,
+    },
 
     /// HTTP transport error.
     #[error("HTTP error: {0}")]
-    Http(#[from] reqwest::Error),
+    Http(#[source] Arc<reqwest::Error>),
 
     /// JSON serialization/deserialization error.
     #[error("JSON error: {0}")]
-    Json(#[from] serde_json::Error),
+    Json(#[source] Arc<serde_json::Error>),
 
     /// Invalid configuration.
     #[error("configuration error: {0}")]
     Config(String),
+
+    /// Request was not sent because the circuit breaker is open.
+    ///
+    /// This error is returned while the API is considered unavailable after
+    /// repeated failures. See [`CircuitBreakerConfig`](crate::CircuitBreakerConfig).
+    #[error("circuit breaker open: API unavailable")]
+    CircuitOpen {
+        /// Time until probe requests are let through, if known.
+        retry_after: Option<Duration>,
+    },
+
+    /// Request was cancelled.
+    ///
+    /// This error is returned when the cancellation token of the request's
+    /// [`RequestOptions`](crate::RequestOptions) is cancelled before it completes.
+    #[error("request cancelled")]
+    Cancelled,
+
+    /// Request is not in the disk cache.
+    ///
+    /// This error is returned in offline mode when the requested path and
+    /// query parameters have not been cached.
+    #[error("not in offline cache: {path}")]
+    CacheMiss {
+        /// Path and query string that was requested.
+        path: String,
+    },
+
+    /// The server returned a pagination cursor that was already followed.
+    ///
+    /// This error ends a [`Paginator`](crate::Paginator) stream instead of
+    /// requesting the same pages forever.
+    #[error("repeated pagination cursor {cursor} for {path}")]
+    RepeatedCursor {
+        /// Path of the paginated endpoint.
+        path: String,
+        /// Cursor returned again by the server.
+        cursor: String,
+    },
+
+    /// Request is not in the replayed cassette.
+    ///
+    /// This error is returned in replay mode when no interaction with the
+    /// requested method, path and query parameters was recorded.
+    #[error("no recorded interaction in cassette for {request}")]
+    CassetteMiss {
+        /// Method, path and query string that was requested.
+        request: String,
+    },
+}
+
+/// A field-level problem reported by the API.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ErrorDetail {
+    /// Name of the rejected parameter, if the problem is specific to one.
+    pub field: Option<String>,
+    /// Why the value was rejected.
+    #[serde(alias = "message")]
+    pub reason: String,
+}
+
+/// Context of an error response from the API.
+///
+/// Carried by [`Error::Validation`] and [`Error::Api`].
+///
+/// # Example
+///
+/// ```rust,ignore
+/// if let Err(Error::Validation { message, context, .. }) = result {
+///     eprintln!("{} ({})", message, context.path);
+///     for detail in &context.details {
+///         eprintln!("  {:?}: {}", detail.field, detail.reason);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// Raw response body text.
+    pub body: String,
+    /// Field-level details from the `details` array of the response.
+    pub details: Vec<ErrorDetail>,
+    /// Link to documentation about the error, if the API provided one.
+    pub help_url: Option<String>,
+    /// Path of the rejected request.
+    pub path: String,
+    /// Query parameters of the rejected request, sorted by name.
+    pub query: Vec<(String, String)>,
+}
+
+/// Fields of a JSON error response body.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct ErrorBody {
+    error: Option<String>,
+    message: Option<String>,
+    code: Option<String>,
+    details: Vec<serde_json::Value>,
+    #[serde(alias = "help_url")]
+    help_url: Option<String>,
+}
+
+/// Parsed error response.
+pub(crate) struct ParsedErrorBody {
+    pub(crate) message: Option<String>,
+    pub(crate) code: Option<String>,
+    pub(crate) context: Box<ErrorContext>,
+}
+
+impl ErrorContext {
+    /// Parse an error response body for the given request.
+    ///
+    /// The message is taken from the `error` or `message` field of a JSON
+    /// body, or else from the body text itself.
+    pub(crate) fn parse(path: &str, query: &[(String, String)], body: &[u8]) -> ParsedErrorBody {
+        let text = String::from_utf8_lossy(body).into_owned();
+        let parsed: ErrorBody = serde_json::from_slice(body).unwrap_or_default();
+
+        let message = parsed.error.or(parsed.message).or_else(|| {
+            let text = text.trim();
+            if text.is_empty() || text.starts_with('{') || text.starts_with('[') {
+                return None;
+            }
+            Some(text.chars().take(MAX_TEXT_MESSAGE_LEN).collect())
+        });
+        let details = parsed
+            .details
+            .into_iter()
+            .filter_map(|detail| match detail {
+                serde_json::Value::String(reason) => Some(ErrorDetail {
+                    field: None,
+                    reason,
+                }),
+                detail => serde_json::from_value(detail).ok(),
+            })
+            .collect();
+
+        ParsedErrorBody {
+            message,
+            code: parsed.code,
+            context: Box::new(Self {
+                body: text,
+                details,
+                help_url: parsed.help_url,
+                path: path.to_string(),
+                query: query.to_vec(),
+            }),
+        }
+    }
+}
+
+impl Error {
+    /// Classify a transport error, given the timeout that applied to the request.
+    pub(crate) fn from_transport(error: reqwest::Error, timeout: Duration) -> Self {
+        let source = Arc::new(error);
+        if source.is_timeout() {
+            Self::Timeout {
+                timeout,
+                source: Some(source),
+            }
+        } else if source.is_connect() {
+            Self::Connect { source }
+        } else if source.is_body() || source.is_decode() {
+            Self::Decode { source }
+        } else {
+            Self::Http(source)
+        }
+    }
+
+    /// Whether the request failed before a response was received.
+    pub(crate) fn is_transport(&self) -> bool {
+        match self {
+            Self::Timeout { .. } | Self::Connect { .. } => true,
+            Self::Http(e) => e.is_request(),
+            _ => false,
+        }
+    }
+
+    /// Whether retrying the request may succeed.
+    ///
+    /// True for rate limiting, timeouts, connection failures and gateway
+    /// errors (HTTP 502, 503 and 504), the failures retried by the default
+    /// [`RetryPolicy`](crate::RetryPolicy).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// match client.filings().list(&params).await {
+    ///     Err(e) if e.is_retryable() => reschedule(e.retry_after()),
+    ///     result => handle(result?),
+    /// }
+    /// ```
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RateLimit { .. } => true,
+            Self::Api { status, .. } => DEFAULT_RETRY_STATUSES.contains(status),
+            _ => self.is_transport(),
+        }
+    }
+
+    /// The HTTP status code of the response, if one was received.
+    #[must_use]
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::Authentication => Some(401),
+            Self::RateLimit { .. } => Some(429),
+            Self::NotFound { .. } => Some(404),
+            Self::Validation { .. } => Some(400),
+            Self::Api { status, .. } => Some(*status),
+            Self::Http(e) => e.status().map(|s| s.as_u16()),
+            _ => None,
+        }
+    }
+
+    /// How long to wait before retrying a rate-limited request.
+    ///
+    /// Taken from the `Retry-After` header, or else computed from the
+    /// `X-RateLimit-Reset` timestamp. For [`Error::CircuitOpen`], the time
+    /// until probe requests are let through. Returns `None` for other
+    /// errors, or if the reset time has already passed.
+    #[must_use]
+    pub fn retry_after(&self) -> Option<Duration> {
+        let (reset_at, retry_after) = match self {
+            Self::RateLimit {
+                reset_at,
+                retry_after,
+                ..
+            } => (reset_at, retry_after),
+            Self::CircuitOpen { retry_after } => return *retry_after,
+            _ => return None,
+        };
+
+        retry_after.or_else(|| {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            reset_at
+                .filter(|reset_at| *reset_at > now)
+                .map(|reset_at| Duration::from_secs(reset_at - now))
+        })
+    }
+
+    /// Context of the error response, for validation and other API errors.
+    #[must_use]
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::Validation { context, .. } | Self::Api { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// The server-assigned request ID, if the API returned one.
+    ///
+    /// Include it when contacting support about a failed request.
+    #[must_use]
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            Self::RateLimit { request_id, .. }
+            | Self::NotFound { request_id, .. }
+            | Self::Validation { request_id, .. }
+            | Self::Api { request_id, .. } => request_id.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Http(Arc::new(error))
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(Arc::new(error))
+    }
 }
 
 /// A specialized `Result` type for EarningsFeed operations.
@@ -97,6 +427,8 @@ mod tests {
     fn test_rate_limit_error_with_reset_at() {
         let err = Error::RateLimit {
             reset_at: Some(1703520000),
+            retry_after: None,
+            request_id: None,
         };
         assert_eq!(
             err.to_string(),
@@ -106,7 +438,11 @@ mod tests {
 
     #[test]
     fn test_rate_limit_error_without_reset_at() {
-        let err = Error::RateLimit { reset_at: None };
+        let err = Error::RateLimit {
+            reset_at: None,
+            retry_after: None,
+            request_id: None,
+        };
         assert_eq!(err.to_string(), "rate limit exceeded (resets at: None)");
     }
 
@@ -114,6 +450,7 @@ mod tests {
     fn test_not_found_error_display() {
         let err = Error::NotFound {
             path: "/api/v1/filings/invalid".to_string(),
+            request_id: None,
         };
         assert_eq!(
             err.to_string(),
@@ -125,6 +462,8 @@ mod tests {
     fn test_validation_error_display() {
         let err = Error::Validation {
             message: "limit must be between 1 and 100".to_string(),
+            request_id: None,
+            context: Box::default(),
         };
         assert_eq!(
             err.to_string(),
@@ -138,6 +477,8 @@ mod tests {
             status: 500,
             message: "Internal server error".to_string(),
             code: Some("INTERNAL_ERROR".to_string()),
+            request_id: None,
+            context: Box::default(),
         };
         assert_eq!(err.to_string(), "API error (500): Internal server error");
     }
@@ -148,13 +489,18 @@ mod tests {
             status: 503,
             message: "Service unavailable".to_string(),
             code: None,
+            request_id: None,
+            context: Box::default(),
         };
         assert_eq!(err.to_string(), "API error (503): Service unavailable");
     }
 
     #[test]
     fn test_timeout_error_display() {
-        let err = Error::Timeout(Duration::from_secs(30));
+        let err = Error::Timeout {
+            timeout: Duration::from_secs(30),
+            source: None,
+        };
         assert_eq!(err.to_string(), "request timeout after 30s");
     }
 
@@ -169,4 +515,159 @@ mod tests {
         fn assert_send_sync<T: Send + Sync>() {}
         assert_send_sync::<Error>();
     }
+
+    #[test]
+    fn test_error_is_clone() {
+        let err = Error::from(serde_json::from_str::<u64>("x").unwrap_err());
+        let cloned = err.clone();
+        assert_eq!(cloned.to_string(), err.to_string());
+        assert!(std::error::Error::source(&cloned).is_some());
+    }
+
+    #[test]
+    fn test_error_classification() {
+        let rate_limit = Error::RateLimit {
+            reset_at: None,
+            retry_after: Some(Duration::from_secs(7)),
+            request_id: Some("req_123".to_string()),
+        };
+        assert!(rate_limit.is_retryable());
+        assert_eq!(rate_limit.status(), Some(429));
+        assert_eq!(rate_limit.retry_after(), Some(Duration::from_secs(7)));
+        assert_eq!(rate_limit.request_id(), Some("req_123"));
+
+        let not_found = Error::NotFound {
+            path: "/api/v1/filings/x".to_string(),
+            request_id: None,
+        };
+        assert!(!not_found.is_retryable());
+        assert_eq!(not_found.status(), Some(404));
+        assert_eq!(not_found.retry_after(), None);
+
+        let timeout = Error::Timeout {
+            timeout: Duration::from_secs(30),
+            source: None,
+        };
+        assert!(timeout.is_retryable());
+        assert_eq!(timeout.status(), None);
+
+        assert!(!Error::Authentication.is_retryable());
+        assert_eq!(Error::Authentication.status(), Some(401));
+        assert!(!Error::Config("bad".to_string()).is_retryable());
+    }
+
+    #[test]
+    fn test_api_error_classification() {
+        let api_error = |status| Error::Api {
+            status,
+            message: "error".to_string(),
+            code: None,
+            request_id: None,
+            context: Box::default(),
+        };
+        for status in [502, 503, 504] {
+            assert!(api_error(status).is_retryable(), "{}", status);
+        }
+        for status in [403, 408, 409, 422, 500, 501] {
+            assert!(!api_error(status).is_retryable(), "{}", status);
+        }
+        assert_eq!(api_error(418).status(), Some(418));
+    }
+
+    #[test]
+    fn test_retry_after_from_reset_timestamp() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let err = |reset_at| Error::RateLimit {
+            reset_at: Some(reset_at),
+            retry_after: None,
+            request_id: None,
+        };
+
+        let delay = err(now + 5).retry_after().unwrap();
+        assert!(delay >= Duration::from_secs(4) && delay <= Duration::from_secs(5));
+        assert_eq!(err(1).retry_after(), None);
+    }
+
+    #[test]
+    fn test_parse_structured_error_body() {
+        let body = br#"{
+            "error": "Invalid parameters",
+            "code": "VALIDATION_ERROR",
+            "details": [
+                { "field": "limit", "reason": "must be between 1 and 100" },
+                { "field": "forms", "message": "unknown form type" },
+                "cursor expired"
+            ],
+            "helpUrl": "https://earningsfeed.com/api/docs#errors"
+        }"#;
+        let query = vec![("limit".to_string(), "500".to_string())];
+        let parsed = ErrorContext::parse("/api/v1/filings", &query, body);
+
+        assert_eq!(parsed.message.as_deref(), Some("Invalid parameters"));
+        assert_eq!(parsed.code.as_deref(), Some("VALIDATION_ERROR"));
+        assert_eq!(
+            parsed.context.details,
+            vec![
+                ErrorDetail {
+                    field: Some("limit".to_string()),
+                    reason: "must be between 1 and 100".to_string(),
+                },
+                ErrorDetail {
+                    field: Some("forms".to_string()),
+                    reason: "unknown form type".to_string(),
+                },
+                ErrorDetail {
+                    field: None,
+                    reason: "cursor expired".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            parsed.context.help_url.as_deref(),
+            Some("https://earningsfeed.com/api/docs#errors")
+        );
+        assert_eq!(parsed.context.path, "/api/v1/filings");
+        assert_eq!(parsed.context.query, query);
+        assert!(parsed.context.body.contains("VALIDATION_ERROR"));
+    }
+
+    #[test]
+    fn test_parse_non_json_error_body() {
+        let parsed = ErrorContext::parse("/api/v1/filings", &[], b"Bad Gateway\n");
+        assert_eq!(parsed.message.as_deref(), Some("Bad Gateway"));
+        assert_eq!(parsed.context.body, "Bad Gateway\n");
+        assert!(parsed.context.details.is_empty());
+
+        let parsed = ErrorContext::parse("/api/v1/filings", &[], b"");
+        assert!(parsed.message.is_none());
+        assert!(parsed.context.body.is_empty());
+
+        let parsed = ErrorContext::parse("/api/v1/filings", &[], br#"{"unexpected": true}"#);
+        assert!(parsed.message.is_none());
+    }
+
+    #[test]
+    fn test_cache_miss_error_display() {
+        let err = Error::CacheMiss {
+            path: "/api/v1/institutional/holdings?cik=1067983".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "not in offline cache: /api/v1/institutional/holdings?cik=1067983"
+        );
+    }
+
+    #[test]
+    fn test_cassette_miss_error_display() {
+        let err = Error::CassetteMiss {
+            request: "GET /api/v1/companies/320193".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "no recorded interaction in cassette for GET /api/v1/companies/320193"
+        );
+    }
 }
@@ -36,18 +36,81 @@
 //! - **Insider Transactions**: Track Form 4 insider trading data
 //! - **Institutional Holdings**: 13F institutional holdings data
 //! - **Company Search**: Search and lookup company profiles
+//! - **Typed Dates**: `chrono` date parameters with range helpers for recent days and calendar quarters
 //! - **Async/Await**: Built on tokio and reqwest
-//! - **Pagination**: Automatic pagination with async streams
+//! - **Pagination**: Automatic pagination with async streams, page limits, prefetching, repeated-cursor protection and resumable checkpoints
+//! - **Bulk Fetch**: Date ranges split into windows paginated in parallel, merged in order without duplicates
+//! - **Retries**: Configurable exponential backoff with rate-limit awareness
+//! - **Rate Limiting**: Optional client-side token bucket shared across clones
+//! - **Circuit Breaker**: Optional fail-fast mode during API outages, shared across clones
+//! - **Middleware**: Hooks around every request for logging, headers and fault injection
+//! - **Caching**: Optional in-memory response cache with per-resource TTLs
+//! - **Disk Cache**: Optional persistent HTTP cache with ETag revalidation and offline mode
+//! - **Record and Replay**: Cassette files of redacted API interactions for offline, deterministic tests
+//! - **Metrics**: Optional per-endpoint request counts, latencies and error rates (`metrics` feature for the `metrics` crate)
+//! - **Request Coalescing**: Concurrent identical requests share one HTTP request
+//! - **Per-Request Options**: Timeout overrides, extra headers, correlation IDs and cancellation
+//! - **Credentials**: Redacted API keys, credential providers and key rotation after a 401
+//! - **Transport Options**: Proxies, pool tuning, root certificates, compression and HTTP/2
+//! - **Configuration Profiles**: Load settings from `EARNINGSFEED_*` variables and JSON/TOML profile files
+//! - **Mocking**: `EarningsFeedApi` trait, an in-memory `MockEarningsFeed` and a fake API server with bundled fixtures (`testing` feature)
+//! - **Blocking Client**: Synchronous client with lazy page iterators (`blocking` feature)
+//! - **Tracing**: Optional spans for every request and pagination loop (`tracing` feature)
 
+mod api;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod bulk;
+mod cache;
+mod cassette;
+mod circuit_breaker;
 mod client;
 mod config;
+mod credentials;
+mod disk_cache;
 mod error;
+mod metrics;
+mod middleware;
 mod models;
+mod options;
+mod pagination;
+mod profile;
+mod rate_limit;
 mod resources;
+mod response;
+mod retry;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod trace;
+mod transport;
 
+pub use api::EarningsFeedApi;
+pub use bulk::{BulkFetch, Sharding, DEFAULT_BULK_CONCURRENCY};
+pub use cache::{
+    CacheConfig, CacheConfigBuilder, CacheResource, CacheStats, ResponseCache,
+    DEFAULT_CACHE_MAX_ENTRIES, DEFAULT_PROVISIONAL_TTL,
+};
+pub use cassette::{CassetteConfig, CassetteMode};
+pub use circuit_breaker::{
+    CircuitBreakerConfig, CircuitBreakerConfigBuilder, CircuitEvent, CircuitHook, CircuitState,
+    DEFAULT_COOL_DOWN, DEFAULT_FAILURE_THRESHOLD,
+};
 pub use client::EarningsFeed;
 pub use config::{ClientConfig, ClientConfigBuilder, DEFAULT_BASE_URL, DEFAULT_TIMEOUT};
-pub use error::{Error, Result};
+pub use credentials::{ApiKey, CredentialProvider, EnvCredentials, FileCredentials};
+pub use disk_cache::{DiskCacheConfig, DEFAULT_DISK_CACHE_MAX_BYTES};
+pub use error::{Error, ErrorContext, ErrorDetail, Result};
+pub use metrics::{EndpointMetrics, LatencyHistogram, MetricsSnapshot};
+pub use middleware::{HttpRequest, HttpResponse, Middleware, Next};
+pub use rate_limit::RateLimiterConfig;
+pub use response::{RateLimitInfo, Response, ResponseMeta};
+pub use retry::{RetryEvent, RetryHook, RetryPolicy, RetryPolicyBuilder};
+pub use transport::TransportConfig;
+pub use reqwest::{Certificate, Proxy};
+pub use pagination::{Paginated, PaginationCheckpoint, Paginator};
+pub use options::{RequestOptions, CORRELATION_ID_HEADER};
+pub use tokio_util::sync::CancellationToken;
+pub use profile::{ENV_CONFIG_FILE, ENV_PROFILE};
 pub use models::{
     // Common
     PaginatedResponse,
@@ -61,5 +124,5 @@ pub use models::{
     Address, Company, CompanySearchResult, SicCode, Ticker,
     // Parameter types
     FilingStatus, ListFilingsParams, ListInsiderParams, ListInstitutionalParams,
-    PutCallFilter, SearchCompaniesParams, TransactionDirection,
+    PutCallFilter, Quarter, SearchCompaniesParams, TransactionDirection,
 };
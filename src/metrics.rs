@@ -0,0 +1,340 @@
+//! Client metrics.
+//!
+//! This module provides an opt-in registry of per-endpoint request
+//! statistics, enabled with
+//! [`ClientConfigBuilder::metrics`](crate::ClientConfigBuilder::metrics).
+//! With the `metrics` feature, the same measurements are also emitted
+//! through the [`metrics`](https://docs.rs/metrics) crate facade.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Upper bounds of the latency histogram buckets, in milliseconds.
+const LATENCY_BUCKETS_MS: [u64; 12] =
+    [5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10000, 30000];
+
+/// Point-in-time copy of the client's metrics.
+///
+/// Returned by [`EarningsFeed::metrics`](crate::EarningsFeed::metrics).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    /// Metrics per endpoint, keyed by path template
+    /// (e.g. `/api/v1/filings/{accession_number}`).
+    pub endpoints: BTreeMap<String, EndpointMetrics>,
+}
+
+impl MetricsSnapshot {
+    /// Metrics of an endpoint, if it has been used.
+    #[must_use]
+    pub fn endpoint(&self, endpoint: &str) -> Option<&EndpointMetrics> {
+        self.endpoints.get(endpoint)
+    }
+
+    /// Total number of HTTP requests sent, across all endpoints.
+    #[must_use]
+    pub fn requests(&self) -> u64 {
+        self.endpoints.values().map(|m| m.requests).sum()
+    }
+}
+
+/// Metrics of a single endpoint.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EndpointMetrics {
+    /// HTTP requests sent, including retries.
+    pub requests: u64,
+    /// Requests that failed with a 4xx or 5xx status or a transport error.
+    pub errors: u64,
+    /// Responses received, by status code.
+    pub statuses: BTreeMap<u16, u64>,
+    /// Response body bytes received.
+    pub bytes_received: u64,
+    /// Request latency distribution.
+    pub latency: LatencyHistogram,
+    /// Pages fetched by pagination streams.
+    pub pages: u64,
+    /// Requests answered from the in-memory cache.
+    pub cache_hits: u64,
+    /// Requests not found in the in-memory cache.
+    pub cache_misses: u64,
+}
+
+impl EndpointMetrics {
+    /// Fraction of requests that failed (0.0 - 1.0).
+    #[must_use]
+    pub fn error_rate(&self) -> f64 {
+        if self.requests == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.requests as f64
+        }
+    }
+}
+
+/// Histogram of request latencies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencyHistogram {
+    /// Number of requests per bucket, by upper bound. The last bucket has
+    /// no upper bound.
+    pub buckets: Vec<(Option<Duration>, u64)>,
+    /// Number of recorded requests.
+    pub count: u64,
+    /// Sum of all recorded latencies.
+    pub sum: Duration,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        let buckets = LATENCY_BUCKETS_MS
+            .iter()
+            .map(|ms| Some(Duration::from_millis(*ms)))
+            .chain(std::iter::once(None))
+            .map(|le| (le, 0))
+            .collect();
+        Self {
+            buckets,
+            count: 0,
+            sum: Duration::ZERO,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    fn record(&mut self, latency: Duration) {
+        self.count += 1;
+        self.sum += latency;
+        if let Some(bucket) = self
+            .buckets
+            .iter_mut()
+            .find(|(le, _)| le.map_or(true, |le| latency <= le))
+        {
+            bucket.1 += 1;
+        }
+    }
+
+    /// Mean latency, if any requests were recorded.
+    #[must_use]
+    pub fn mean(&self) -> Option<Duration> {
+        u32::try_from(self.count)
+            .ok()
+            .filter(|count| *count > 0)
+            .map(|count| self.sum / count)
+    }
+
+    /// Upper bound of the bucket containing the given quantile (0.0 - 1.0).
+    ///
+    /// Returns `None` if no requests were recorded or the quantile falls
+    /// in the unbounded bucket.
+    #[must_use]
+    pub fn quantile(&self, quantile: f64) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = (quantile.clamp(0.0, 1.0) * self.count as f64)
+            .ceil()
+            .max(1.0) as u64;
+        let mut seen = 0;
+        for (le, count) in &self.buckets {
+            seen += count;
+            if seen >= rank {
+                return *le;
+            }
+        }
+        None
+    }
+}
+
+/// Path template of an API path, used as the endpoint label.
+pub(crate) fn endpoint(path: &str) -> String {
+    const TEMPLATES: [(&str, &str); 2] = [
+        ("/api/v1/filings/", "/api/v1/filings/{accession_number}"),
+        ("/api/v1/companies/", "/api/v1/companies/{cik}"),
+    ];
+
+    if path == "/api/v1/companies/search" {
+        return path.to_string();
+    }
+    TEMPLATES
+        .iter()
+        .find(|(prefix, _)| path.len() > prefix.len() && path.starts_with(prefix))
+        .map_or_else(|| path.to_string(), |(_, template)| template.to_string())
+}
+
+/// Registry of per-endpoint metrics, shared by all clones of a client.
+#[derive(Debug, Default)]
+pub(crate) struct Metrics {
+    endpoints: Mutex<HashMap<String, EndpointMetrics>>,
+}
+
+impl Metrics {
+    fn update(&self, endpoint: &str, f: impl FnOnce(&mut EndpointMetrics)) {
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        match endpoints.get_mut(endpoint) {
+            Some(metrics) => f(metrics),
+            None => f(endpoints.entry(endpoint.to_string()).or_default()),
+        }
+    }
+
+    /// Record a response received from the API.
+    pub(crate) fn record_response(&self, path: &str, status: u16, bytes: usize, latency: Duration) {
+        let endpoint = endpoint(path);
+        self.update(&endpoint, |m| {
+            m.requests += 1;
+            m.errors += u64::from(status >= 400);
+            *m.statuses.entry(status).or_default() += 1;
+            m.bytes_received += bytes as u64;
+            m.latency.record(latency);
+        });
+
+        #[cfg(feature = "metrics")]
+        {
+            let labels = [("endpoint", endpoint), ("status", status.to_string())];
+            ::metrics::counter!("earningsfeed_requests_total", &labels).increment(1);
+            ::metrics::counter!("earningsfeed_response_bytes_total", &labels[..1])
+                .increment(bytes as u64);
+            ::metrics::histogram!("earningsfeed_request_duration_seconds", &labels[..1])
+                .record(latency.as_secs_f64());
+        }
+    }
+
+    /// Record a request that failed without a response.
+    pub(crate) fn record_error(&self, path: &str, latency: Duration) {
+        let endpoint = endpoint(path);
+        self.update(&endpoint, |m| {
+            m.requests += 1;
+            m.errors += 1;
+            m.latency.record(latency);
+        });
+
+        #[cfg(feature = "metrics")]
+        {
+            let labels = [("endpoint", endpoint), ("status", "error".to_string())];
+            ::metrics::counter!("earningsfeed_requests_total", &labels).increment(1);
+            ::metrics::histogram!("earningsfeed_request_duration_seconds", &labels[..1])
+                .record(latency.as_secs_f64());
+        }
+    }
+
+    /// Record a page fetched by a pagination stream.
+    pub(crate) fn record_page(&self, path: &str) {
+        let endpoint = endpoint(path);
+        self.update(&endpoint, |m| m.pages += 1);
+
+        #[cfg(feature = "metrics")]
+        ::metrics::counter!("earningsfeed_pages_total", "endpoint" => endpoint).increment(1);
+    }
+
+    /// Record an in-memory cache lookup.
+    pub(crate) fn record_cache(&self, path: &str, hit: bool) {
+        let endpoint = endpoint(path);
+        self.update(&endpoint, |m| {
+            if hit {
+                m.cache_hits += 1;
+            } else {
+                m.cache_misses += 1;
+            }
+        });
+
+        #[cfg(feature = "metrics")]
+        {
+            let name = if hit {
+                "earningsfeed_cache_hits_total"
+            } else {
+                "earningsfeed_cache_misses_total"
+            };
+            ::metrics::counter!(name, "endpoint" => endpoint).increment(1);
+        }
+    }
+
+    /// Copy the current metrics.
+    pub(crate) fn snapshot(&self) -> MetricsSnapshot {
+        let endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        MetricsSnapshot {
+            endpoints: endpoints
+                .iter()
+                .map(|(endpoint, metrics)| (endpoint.clone(), metrics.clone()))
+                .collect(),
+        }
+    }
+
+    /// Reset all metrics to zero.
+    pub(crate) fn reset(&self) {
+        self.endpoints
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_templates() {
+        assert_eq!(endpoint("/api/v1/filings"), "/api/v1/filings");
+        assert_eq!(
+            endpoint("/api/v1/filings/0000950170-24-000001"),
+            "/api/v1/filings/{accession_number}"
+        );
+        assert_eq!(
+            endpoint("/api/v1/companies/320193"),
+            "/api/v1/companies/{cik}"
+        );
+        assert_eq!(
+            endpoint("/api/v1/companies/search"),
+            "/api/v1/companies/search"
+        );
+        assert_eq!(
+            endpoint("/api/v1/insider/transactions"),
+            "/api/v1/insider/transactions"
+        );
+    }
+
+    #[test]
+    fn test_records_per_endpoint() {
+        let metrics = Metrics::default();
+        metrics.record_response("/api/v1/filings", 200, 100, Duration::from_millis(40));
+        metrics.record_response("/api/v1/filings", 503, 10, Duration::from_millis(400));
+        metrics.record_error("/api/v1/filings", Duration::from_secs(30));
+        metrics.record_page("/api/v1/filings");
+        metrics.record_cache("/api/v1/companies/320193", true);
+        metrics.record_cache("/api/v1/companies/1045810", false);
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.requests(), 3);
+
+        let filings = snapshot.endpoint("/api/v1/filings").unwrap();
+        assert_eq!(filings.requests, 3);
+        assert_eq!(filings.errors, 2);
+        assert_eq!(filings.statuses, BTreeMap::from([(200, 1), (503, 1)]));
+        assert_eq!(filings.bytes_received, 110);
+        assert_eq!(filings.pages, 1);
+        assert!((filings.error_rate() - 2.0 / 3.0).abs() < 1e-9);
+
+        let companies = snapshot.endpoint("/api/v1/companies/{cik}").unwrap();
+        assert_eq!((companies.cache_hits, companies.cache_misses), (1, 1));
+        assert_eq!(companies.requests, 0);
+
+        metrics.reset();
+        assert_eq!(metrics.snapshot(), MetricsSnapshot::default());
+    }
+
+    #[test]
+    fn test_latency_histogram() {
+        let mut histogram = LatencyHistogram::default();
+        assert_eq!(histogram.mean(), None);
+        assert_eq!(histogram.quantile(0.5), None);
+
+        for ms in [3, 20, 20, 80, 45000] {
+            histogram.record(Duration::from_millis(ms));
+        }
+
+        assert_eq!(histogram.count, 5);
+        assert_eq!(histogram.mean(), Some(Duration::from_micros(9_024_600)));
+        assert_eq!(histogram.quantile(0.0), Some(Duration::from_millis(5)));
+        assert_eq!(histogram.quantile(0.5), Some(Duration::from_millis(25)));
+        assert_eq!(histogram.quantile(0.8), Some(Duration::from_millis(100)));
+        assert_eq!(histogram.quantile(1.0), None);
+    }
+}
@@ -0,0 +1,609 @@
+//! Middleware hooks around the HTTP layer.
+//!
+//! This module provides the [`Middleware`] trait and the [`Next`] handle to
+//! the rest of the chain, along with the [`HttpRequest`] and
+//! [`HttpResponse`] types that middleware operate on.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::Method;
+
+use crate::error::Result;
+
+/// An outgoing API request, as seen by middleware.
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    /// HTTP method.
+    pub method: Method,
+    /// API path (e.g., "/api/v1/filings").
+    pub path: String,
+    /// Query parameters, sorted by name.
+    pub query: Vec<(String, String)>,
+    /// Request headers, in addition to the client's default headers.
+    pub headers: HeaderMap,
+    /// Timeout overriding the client-wide timeout.
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    /// Create a new GET request for the given path and query parameters.
+    #[must_use]
+    pub fn get(path: impl Into<String>, query: Vec<(String, String)>) -> Self {
+        Self {
+            method: Method::GET,
+            path: path.into(),
+            query,
+            headers: HeaderMap::new(),
+            timeout: None,
+        }
+    }
+
+    /// The URL-encoded query string, without the leading `?`.
+    #[must_use]
+    pub fn query_string(&self) -> String {
+        serde_urlencoded::to_string(&self.query).unwrap_or_default()
+    }
+
+    /// The path followed by the encoded query string, if any.
+    #[must_use]
+    pub fn path_and_query(&self) -> String {
+        if self.query.is_empty() {
+            self.path.clone()
+        } else {
+            format!("{}?{}", self.path, self.query_string())
+        }
+    }
+}
+
+/// A raw API response, as seen by middleware.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    /// HTTP status code.
+    pub status: u16,
+    /// Response headers.
+    pub headers: HeaderMap,
+    /// Raw response body.
+    pub body: Bytes,
+}
+
+impl HttpResponse {
+    /// Create a new response with the given status and an empty body.
+    #[must_use]
+    pub fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: Bytes::new(),
+        }
+    }
+
+    /// Create a new response with the given status and JSON body.
+    #[must_use]
+    pub fn json(status: u16, body: &serde_json::Value) -> Self {
+        let mut response = Self::new(status).with_body(body.to_string());
+        response.headers.insert(
+            reqwest::header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        );
+        response
+    }
+
+    /// Set a response header.
+    ///
+    /// Invalid header names or values are ignored.
+    #[must_use]
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            self.headers.insert(name, value);
+        }
+        self
+    }
+
+    /// Set the response body.
+    #[must_use]
+    pub fn with_body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = body.into();
+        self
+    }
+}
+
+/// Hooks that run around every HTTP request made by the client.
+///
+/// Middleware are registered with
+/// [`ClientConfigBuilder::middleware`](crate::ClientConfigBuilder::middleware)
+/// and wrap each other in registration order: the first middleware sees
+/// the request first and the response last. They run once per attempt, so
+/// a request retried by the client's [`RetryPolicy`](crate::RetryPolicy)
+/// passes through the chain again.
+///
+/// Most middleware only need the synchronous hooks.
+/// [`on_request`](Self::on_request) runs before the request is passed on,
+/// and may modify it or return a response to short-circuit the chain: the
+/// request is not sent, later middleware are skipped, and earlier
+/// middleware see the returned response. [`on_response`](Self::on_response)
+/// runs once the response comes back. Returning an error from either hook
+/// aborts the attempt with that error.
+///
+/// Middleware that need to wait, or to send a request more than once,
+/// override [`handle`](Self::handle) instead, which receives the rest of
+/// the chain as [`Next`].
+///
+/// # Example
+///
+/// ```rust
+/// use earningsfeed::{ClientConfig, HttpRequest, HttpResponse, Middleware, Result};
+///
+/// struct GatewayHeader;
+///
+/// impl Middleware for GatewayHeader {
+///     fn on_request(&self, request: &mut HttpRequest) -> Result<Option<HttpResponse>> {
+///         request
+///             .headers
+///             .insert("X-Gateway-Tenant", "research".parse().unwrap());
+///         Ok(None)
+///     }
+/// }
+///
+/// let config = ClientConfig::builder()
+///     .api_key("your_api_key")
+///     .middleware(GatewayHeader)
+///     .build()
+///     .unwrap();
+/// ```
+///
+/// Injecting latency with [`handle`](Self::handle):
+///
+/// ```rust
+/// use earningsfeed::{HttpRequest, HttpResponse, Middleware, Next, Result};
+/// use futures::future::BoxFuture;
+/// use std::time::Duration;
+///
+/// struct Latency(Duration);
+///
+/// impl Middleware for Latency {
+///     fn handle<'a>(
+///         &'a self,
+///         request: HttpRequest,
+///         next: Next<'a>,
+///     ) -> BoxFuture<'a, Result<HttpResponse>> {
+///         Box::pin(async move {
+///             tokio::time::sleep(self.0).await;
+///             next.run(request).await
+///         })
+///     }
+/// }
+/// ```
+pub trait Middleware: Send + Sync + 'static {
+    /// Called before a request is passed on.
+    ///
+    /// May modify the request, or return a response to short-circuit the chain.
+    fn on_request(&self, request: &mut HttpRequest) -> Result<Option<HttpResponse>> {
+        let _ = request;
+        Ok(None)
+    }
+
+    /// Called after a response is received.
+    ///
+    /// May inspect or modify the response before it is mapped to a result.
+    fn on_response(&self, request: &HttpRequest, response: &mut HttpResponse) -> Result<()> {
+        let _ = (request, response);
+        Ok(())
+    }
+
+    /// Handle a request, passing it on to the rest of the chain with
+    /// [`Next::run`].
+    ///
+    /// Defaults to running [`on_request`](Self::on_request), the rest of the
+    /// chain, and [`on_response`](Self::on_response). Overrides may await
+    /// before or after passing the request on, call `next` several times,
+    /// or not at all.
+    fn handle<'a>(
+        &'a self,
+        mut request: HttpRequest,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<HttpResponse>> {
+        Box::pin(async move {
+            if let Some(response) = self.on_request(&mut request)? {
+                return Ok(response);
+            }
+            let mut response = next.run(request.clone()).await?;
+            self.on_response(&request, &mut response)?;
+            Ok(response)
+        })
+    }
+
+    /// Name of this middleware, used in debug output.
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+}
+
+impl<M: Middleware + ?Sized> Middleware for Arc<M> {
+    fn on_request(&self, request: &mut HttpRequest) -> Result<Option<HttpResponse>> {
+        (**self).on_request(request)
+    }
+
+    fn on_response(&self, request: &HttpRequest, response: &mut HttpResponse) -> Result<()> {
+        (**self).on_response(request, response)
+    }
+
+    fn handle<'a>(
+        &'a self,
+        request: HttpRequest,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<HttpResponse>> {
+        (**self).handle(request, next)
+    }
+
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+}
+
+impl std::fmt::Debug for dyn Middleware {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Sends a request over the network, at the end of the chain.
+type SendRequest<'a> =
+    dyn Fn(HttpRequest) -> BoxFuture<'a, Result<HttpResponse>> + Send + Sync + 'a;
+
+/// The rest of the middleware chain, passed to [`Middleware::handle`].
+///
+/// `Next` is `Copy`, so a middleware may run the rest of the chain more
+/// than once, e.g. to retry a request.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    middleware: &'a [Arc<dyn Middleware>],
+    send: &'a SendRequest<'a>,
+}
+
+impl<'a> Next<'a> {
+    /// Run the request through the remaining middleware, then send it
+    /// unless one of them short-circuits it.
+    pub fn run(self, request: HttpRequest) -> BoxFuture<'a, Result<HttpResponse>> {
+        match self.middleware.split_first() {
+            Some((first, middleware)) => first.handle(
+                request,
+                Next {
+                    middleware,
+                    send: self.send,
+                },
+            ),
+            None => (self.send)(request),
+        }
+    }
+}
+
+impl std::fmt::Debug for Next<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Next")
+            .field("middleware", &self.middleware)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Run a request through the middleware chain.
+///
+/// `send` is only called if no middleware short-circuits the request.
+pub(crate) async fn run<'a>(
+    middleware: &'a [Arc<dyn Middleware>],
+    request: HttpRequest,
+    send: &'a SendRequest<'a>,
+) -> Result<HttpResponse> {
+    Next { middleware, send }.run(request).await
+}
+
+/// Run blocking work of a middleware, such as file system access, on the
+/// blocking thread pool.
+pub(crate) async fn spawn_blocking<R: Send + 'static>(
+    f: impl FnOnce() -> R + Send + 'static,
+) -> Result<R> {
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => Ok(result),
+        Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+        Err(_) => Err(crate::error::Error::Cancelled),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::EarningsFeed;
+    use crate::error::Error;
+    use std::sync::Mutex;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn setup_client(
+        mock_server: &MockServer,
+        middleware: Vec<Arc<dyn Middleware>>,
+    ) -> EarningsFeed {
+        let mut builder = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri());
+        for m in middleware {
+            builder = builder.middleware(m);
+        }
+        EarningsFeed::with_config(builder.build().unwrap()).unwrap()
+    }
+
+    struct AddHeader;
+
+    impl Middleware for AddHeader {
+        fn on_request(&self, request: &mut HttpRequest) -> Result<Option<HttpResponse>> {
+            request
+                .headers
+                .insert("X-Gateway-Tenant", HeaderValue::from_static("research"));
+            Ok(None)
+        }
+    }
+
+    struct ShortCircuit;
+
+    impl Middleware for ShortCircuit {
+        fn on_request(&self, _request: &mut HttpRequest) -> Result<Option<HttpResponse>> {
+            Ok(Some(HttpResponse::json(
+                200,
+                &serde_json::json!({"items": [], "nextCursor": null, "hasMore": false}),
+            )))
+        }
+    }
+
+    struct FailRequests;
+
+    impl Middleware for FailRequests {
+        fn on_request(&self, _request: &mut HttpRequest) -> Result<Option<HttpResponse>> {
+            Err(Error::Config("blocked by middleware".into()))
+        }
+    }
+
+    /// Retries server errors once, after a short pause.
+    struct RetryServerErrors;
+
+    impl Middleware for RetryServerErrors {
+        fn handle<'a>(
+            &'a self,
+            request: HttpRequest,
+            next: Next<'a>,
+        ) -> BoxFuture<'a, Result<HttpResponse>> {
+            Box::pin(async move {
+                let response = next.run(request.clone()).await?;
+                if response.status < 500 {
+                    return Ok(response);
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                next.run(request).await
+            })
+        }
+    }
+
+    struct Recorder {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Middleware for Recorder {
+        fn on_request(&self, request: &mut HttpRequest) -> Result<Option<HttpResponse>> {
+            self.log.lock().unwrap().push(format!(
+                "{} request {}",
+                self.name,
+                request.path_and_query()
+            ));
+            Ok(None)
+        }
+
+        fn on_response(&self, _request: &HttpRequest, response: &mut HttpResponse) -> Result<()> {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("{} response {}", self.name, response.status));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_request_query_string() {
+        let request = HttpRequest::get(
+            "/api/v1/filings",
+            vec![
+                ("forms".to_string(), "10-K,10-Q".to_string()),
+                ("ticker".to_string(), "AAPL".to_string()),
+            ],
+        );
+        assert_eq!(request.query_string(), "forms=10-K%2C10-Q&ticker=AAPL");
+        assert_eq!(
+            request.path_and_query(),
+            "/api/v1/filings?forms=10-K%2C10-Q&ticker=AAPL"
+        );
+        assert_eq!(
+            HttpRequest::get("/api/v1/filings", vec![]).path_and_query(),
+            "/api/v1/filings"
+        );
+    }
+
+    #[test]
+    fn test_response_builders() {
+        let response = HttpResponse::new(429).with_header("X-RateLimit-Reset", "1703520000");
+        assert_eq!(response.status, 429);
+        assert_eq!(response.headers["X-RateLimit-Reset"], "1703520000");
+        assert!(response.body.is_empty());
+
+        let response = HttpResponse::json(200, &serde_json::json!({"ok": true}));
+        assert_eq!(response.body, Bytes::from_static(b"{\"ok\":true}"));
+        assert_eq!(response.headers["content-type"], "application/json");
+    }
+
+    #[test]
+    fn test_middleware_debug_uses_name() {
+        let m: Arc<dyn Middleware> = Arc::new(AddHeader);
+        assert!(format!("{:?}", m).ends_with("AddHeader"));
+    }
+
+    #[tokio::test]
+    async fn test_middleware_adds_header() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .and(header("X-Gateway-Tenant", "research"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [],
+                "nextCursor": null,
+                "hasMore": false
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = setup_client(&mock_server, vec![Arc::new(AddHeader)]).await;
+        let response = client.filings().list(&Default::default()).await.unwrap();
+        assert!(response.items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_middleware_short_circuits() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let outer = Recorder {
+            name: "outer",
+            log: log.clone(),
+        };
+        let inner = Recorder {
+            name: "inner",
+            log: log.clone(),
+        };
+        let client = setup_client(
+            &mock_server,
+            vec![Arc::new(outer), Arc::new(ShortCircuit), Arc::new(inner)],
+        )
+        .await;
+
+        let response = client.filings().list(&Default::default()).await.unwrap();
+        assert!(response.items.is_empty());
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["outer request /api/v1/filings", "outer response 200"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_middleware_runs_in_onion_order() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .and(query_param("ticker", "AAPL"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let first = Recorder {
+            name: "first",
+            log: log.clone(),
+        };
+        let second = Recorder {
+            name: "second",
+            log: log.clone(),
+        };
+        let client = setup_client(&mock_server, vec![Arc::new(first), Arc::new(second)]).await;
+
+        let params = crate::models::ListFilingsParams::builder()
+            .ticker("AAPL")
+            .build();
+        let result = client.filings().list(&params).await;
+
+        assert!(matches!(result, Err(Error::NotFound { .. })));
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "first request /api/v1/filings?ticker=AAPL",
+                "second request /api/v1/filings?ticker=AAPL",
+                "second response 404",
+                "first response 404",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_async_middleware_can_wait_and_resend() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [],
+                "nextCursor": null,
+                "hasMore": false
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let inner = Recorder {
+            name: "inner",
+            log: log.clone(),
+        };
+        let client = setup_client(
+            &mock_server,
+            vec![Arc::new(RetryServerErrors), Arc::new(inner)],
+        )
+        .await;
+
+        let started = std::time::Instant::now();
+        let response = client.filings().list(&Default::default()).await.unwrap();
+        assert!(response.items.is_empty());
+        assert!(started.elapsed() >= Duration::from_millis(20));
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "inner request /api/v1/filings",
+                "inner response 503",
+                "inner request /api/v1/filings",
+                "inner response 200",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_middleware_error_aborts_request() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let client = setup_client(&mock_server, vec![Arc::new(FailRequests)]).await;
+        let result = client.companies().get(320193).await;
+
+        assert!(
+            matches!(result, Err(Error::Config(message)) if message == "blocked by middleware")
+        );
+    }
+}
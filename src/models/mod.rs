@@ -16,5 +16,5 @@ pub use insider::{AcquiredDisposed, DirectIndirect, InsiderTransaction};
 pub use institutional::{InstitutionalHolding, InvestmentDiscretion, PutCall, SharesType};
 pub use params::{
     FilingStatus, ListFilingsParams, ListInsiderParams, ListInstitutionalParams,
-    PutCallFilter, SearchCompaniesParams, TransactionDirection,
+    PutCallFilter, Quarter, SearchCompaniesParams, TransactionDirection,
 };
@@ -2,8 +2,11 @@
 //!
 //! This module contains builder-style parameter types for API requests.
 
+use chrono::{Duration, NaiveDate, Utc};
 use serde::Serialize;
 
+use crate::pagination::Paginated;
+
 /// Filing status filter.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "lowercase")]
@@ -38,6 +41,82 @@ pub enum PutCallFilter {
     Equity,
 }
 
+/// Calendar quarter, for quarter date ranges and 13F report periods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Quarter {
+    /// January to March.
+    Q1,
+    /// April to June.
+    Q2,
+    /// July to September.
+    Q3,
+    /// October to December.
+    Q4,
+}
+
+impl Quarter {
+    /// The quarter with the given number (1-4).
+    #[must_use]
+    pub fn from_number(quarter: u32) -> Option<Self> {
+        match quarter {
+            1 => Some(Self::Q1),
+            2 => Some(Self::Q2),
+            3 => Some(Self::Q3),
+            4 => Some(Self::Q4),
+            _ => None,
+        }
+    }
+
+    /// The first day of the quarter in the given year.
+    ///
+    /// Every `u16` year is within the range of [`NaiveDate`], so this
+    /// cannot fail.
+    #[must_use]
+    pub fn first_day(self, year: u16) -> NaiveDate {
+        let month = match self {
+            Self::Q1 => 1,
+            Self::Q2 => 4,
+            Self::Q3 => 7,
+            Self::Q4 => 10,
+        };
+        quarter_date(year, month, 1)
+    }
+
+    /// The last day of the quarter in the given year.
+    ///
+    /// This is the report period of 13F filings for the quarter.
+    #[must_use]
+    pub fn last_day(self, year: u16) -> NaiveDate {
+        let (month, day) = match self {
+            Self::Q1 => (3, 31),
+            Self::Q2 => (6, 30),
+            Self::Q3 => (9, 30),
+            Self::Q4 => (12, 31),
+        };
+        quarter_date(year, month, day)
+    }
+}
+
+/// A quarter boundary date.
+///
+/// Quarter boundaries exist in every year, and `u16` years are far inside
+/// the range of [`NaiveDate`].
+fn quarter_date(year: u16, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(i32::from(year), month, day)
+        .unwrap_or_else(|| unreachable!("{}-{}-{} is a valid date", year, month, day))
+}
+
+/// The `days` days up to and including today (UTC).
+///
+/// Ranges reaching before the earliest [`NaiveDate`] start there.
+fn last_days(days: u32) -> (NaiveDate, NaiveDate) {
+    let today = Utc::now().date_naive();
+    let start = today
+        .checked_sub_signed(Duration::days(i64::from(days.max(1)) - 1))
+        .unwrap_or(NaiveDate::MIN);
+    (start, today)
+}
+
 /// Parameters for listing filings.
 ///
 /// Use the builder pattern to construct parameters:
@@ -66,12 +145,12 @@ pub struct ListFilingsParams {
     /// Filter by filing status.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<FilingStatus>,
-    /// Start date (YYYY-MM-DD).
+    /// Start date, inclusive.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub start_date: Option<String>,
-    /// End date (YYYY-MM-DD).
+    pub start_date: Option<NaiveDate>,
+    /// End date, inclusive.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub end_date: Option<String>,
+    pub end_date: Option<NaiveDate>,
     /// Search query.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub q: Option<String>,
@@ -91,6 +170,16 @@ impl ListFilingsParams {
     }
 }
 
+impl Paginated for ListFilingsParams {
+    fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
+    }
+
+    fn set_cursor(&mut self, cursor: Option<String>) {
+        self.cursor = cursor;
+    }
+}
+
 /// Builder for [`ListFilingsParams`].
 #[derive(Debug, Default)]
 pub struct ListFilingsParamsBuilder {
@@ -131,20 +220,41 @@ impl ListFilingsParamsBuilder {
         self
     }
 
-    /// Filter by start date (YYYY-MM-DD).
+    /// Filter by start date, inclusive.
     #[must_use]
-    pub fn start_date(mut self, date: impl Into<String>) -> Self {
-        self.params.start_date = Some(date.into());
+    pub fn start_date(mut self, date: NaiveDate) -> Self {
+        self.params.start_date = Some(date);
         self
     }
 
-    /// Filter by end date (YYYY-MM-DD).
+    /// Filter by end date, inclusive.
     #[must_use]
-    pub fn end_date(mut self, date: impl Into<String>) -> Self {
-        self.params.end_date = Some(date.into());
+    pub fn end_date(mut self, date: NaiveDate) -> Self {
+        self.params.end_date = Some(date);
         self
     }
 
+    /// Filter by an inclusive date range, in either order.
+    #[must_use]
+    pub fn between(self, a: NaiveDate, b: NaiveDate) -> Self {
+        self.start_date(a.min(b)).end_date(a.max(b))
+    }
+
+    /// Filter by the last `days` days, including today (UTC).
+    ///
+    /// Values below 1 are treated as 1.
+    #[must_use]
+    pub fn last_days(self, days: u32) -> Self {
+        let (start, end) = last_days(days);
+        self.between(start, end)
+    }
+
+    /// Filter by a calendar quarter.
+    #[must_use]
+    pub fn calendar_quarter(self, year: u16, quarter: Quarter) -> Self {
+        self.between(quarter.first_day(year), quarter.last_day(year))
+    }
+
     /// Search query.
     #[must_use]
     pub fn q(mut self, query: impl Into<String>) -> Self {
@@ -198,12 +308,12 @@ pub struct ListInsiderParams {
     /// Minimum transaction value.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub min_value: Option<u64>,
-    /// Start date (YYYY-MM-DD).
+    /// Start date, inclusive.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub start_date: Option<String>,
-    /// End date (YYYY-MM-DD).
+    pub start_date: Option<NaiveDate>,
+    /// End date, inclusive.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub end_date: Option<String>,
+    pub end_date: Option<NaiveDate>,
     /// Results per page (1-100).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u32>,
@@ -220,6 +330,16 @@ impl ListInsiderParams {
     }
 }
 
+impl Paginated for ListInsiderParams {
+    fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
+    }
+
+    fn set_cursor(&mut self, cursor: Option<String>) {
+        self.cursor = cursor;
+    }
+}
+
 /// Builder for [`ListInsiderParams`].
 #[derive(Debug, Default)]
 pub struct ListInsiderParamsBuilder {
@@ -281,20 +401,41 @@ impl ListInsiderParamsBuilder {
         self
     }
 
-    /// Filter by start date (YYYY-MM-DD).
+    /// Filter by start date, inclusive.
     #[must_use]
-    pub fn start_date(mut self, date: impl Into<String>) -> Self {
-        self.params.start_date = Some(date.into());
+    pub fn start_date(mut self, date: NaiveDate) -> Self {
+        self.params.start_date = Some(date);
         self
     }
 
-    /// Filter by end date (YYYY-MM-DD).
+    /// Filter by end date, inclusive.
     #[must_use]
-    pub fn end_date(mut self, date: impl Into<String>) -> Self {
-        self.params.end_date = Some(date.into());
+    pub fn end_date(mut self, date: NaiveDate) -> Self {
+        self.params.end_date = Some(date);
         self
     }
 
+    /// Filter by an inclusive date range, in either order.
+    #[must_use]
+    pub fn between(self, a: NaiveDate, b: NaiveDate) -> Self {
+        self.start_date(a.min(b)).end_date(a.max(b))
+    }
+
+    /// Filter by the last `days` days, including today (UTC).
+    ///
+    /// Values below 1 are treated as 1.
+    #[must_use]
+    pub fn last_days(self, days: u32) -> Self {
+        let (start, end) = last_days(days);
+        self.between(start, end)
+    }
+
+    /// Filter by a calendar quarter.
+    #[must_use]
+    pub fn calendar_quarter(self, year: u16, quarter: Quarter) -> Self {
+        self.between(quarter.first_day(year), quarter.last_day(year))
+    }
+
     /// Results per page (1-100).
     #[must_use]
     pub fn limit(mut self, limit: u32) -> Self {
@@ -338,9 +479,9 @@ pub struct ListInstitutionalParams {
     /// Filter by put/call/equity.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub put_call: Option<PutCallFilter>,
-    /// Filter by report period (YYYY-MM-DD).
+    /// Filter by report period (the last day of the quarter).
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub report_period: Option<String>,
+    pub report_period: Option<NaiveDate>,
     /// Results per page (1-100).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u32>,
@@ -357,6 +498,16 @@ impl ListInstitutionalParams {
     }
 }
 
+impl Paginated for ListInstitutionalParams {
+    fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
+    }
+
+    fn set_cursor(&mut self, cursor: Option<String>) {
+        self.cursor = cursor;
+    }
+}
+
 /// Builder for [`ListInstitutionalParams`].
 #[derive(Debug, Default)]
 pub struct ListInstitutionalParamsBuilder {
@@ -406,13 +557,19 @@ impl ListInstitutionalParamsBuilder {
         self
     }
 
-    /// Filter by report period (YYYY-MM-DD).
+    /// Filter by report period (the last day of the quarter).
     #[must_use]
-    pub fn report_period(mut self, date: impl Into<String>) -> Self {
-        self.params.report_period = Some(date.into());
+    pub fn report_period(mut self, date: NaiveDate) -> Self {
+        self.params.report_period = Some(date);
         self
     }
 
+    /// Filter by the report period of a calendar quarter.
+    #[must_use]
+    pub fn report_quarter(self, year: u16, quarter: Quarter) -> Self {
+        self.report_period(quarter.last_day(year))
+    }
+
     /// Results per page (1-100).
     #[must_use]
     pub fn limit(mut self, limit: u32) -> Self {
@@ -466,6 +623,16 @@ impl SearchCompaniesParams {
     }
 }
 
+impl Paginated for SearchCompaniesParams {
+    fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
+    }
+
+    fn set_cursor(&mut self, cursor: Option<String>) {
+        self.cursor = cursor;
+    }
+}
+
 /// Builder for [`SearchCompaniesParams`].
 #[derive(Debug, Default)]
 pub struct SearchCompaniesParamsBuilder {
@@ -526,6 +693,10 @@ impl SearchCompaniesParamsBuilder {
 mod tests {
     use super::*;
 
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
     #[test]
     fn test_list_filings_params_builder() {
         let params = ListFilingsParams::builder()
@@ -566,12 +737,58 @@ mod tests {
     #[test]
     fn test_list_filings_params_with_dates() {
         let params = ListFilingsParams::builder()
-            .start_date("2024-01-01")
-            .end_date("2024-12-31")
+            .start_date(date("2024-01-01"))
+            .end_date(date("2024-12-31"))
             .build();
 
-        assert_eq!(params.start_date, Some("2024-01-01".to_string()));
-        assert_eq!(params.end_date, Some("2024-12-31".to_string()));
+        assert_eq!(params.start_date, Some(date("2024-01-01")));
+        assert_eq!(params.end_date, Some(date("2024-12-31")));
+
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(json["startDate"], "2024-01-01");
+        assert_eq!(json["endDate"], "2024-12-31");
+    }
+
+    #[test]
+    fn test_date_range_helpers() {
+        let params = ListFilingsParams::builder()
+            .between(date("2024-03-31"), date("2024-01-01"))
+            .build();
+        assert_eq!(params.start_date, Some(date("2024-01-01")));
+        assert_eq!(params.end_date, Some(date("2024-03-31")));
+
+        let params = ListInsiderParams::builder()
+            .calendar_quarter(2024, Quarter::Q3)
+            .build();
+        assert_eq!(params.start_date, Some(date("2024-07-01")));
+        assert_eq!(params.end_date, Some(date("2024-09-30")));
+
+        let params = ListFilingsParams::builder().last_days(30).build();
+        let (start, end) = (params.start_date.unwrap(), params.end_date.unwrap());
+        assert_eq!(end, Utc::now().date_naive());
+        assert_eq!((end - start).num_days(), 29);
+
+        let params = ListInsiderParams::builder().last_days(0).build();
+        assert_eq!(params.start_date, params.end_date);
+
+        let params = ListFilingsParams::builder().last_days(u32::MAX).build();
+        assert_eq!(params.start_date, Some(NaiveDate::MIN));
+    }
+
+    #[test]
+    fn test_quarter() {
+        assert_eq!(Quarter::from_number(3), Some(Quarter::Q3));
+        assert_eq!(Quarter::from_number(5), None);
+        assert_eq!(Quarter::Q1.first_day(2024), date("2024-01-01"));
+        assert_eq!(Quarter::Q1.last_day(2024), date("2024-03-31"));
+        assert_eq!(Quarter::Q2.last_day(2024), date("2024-06-30"));
+        assert_eq!(Quarter::Q4.first_day(2024), date("2024-10-01"));
+        assert_eq!(Quarter::Q4.last_day(2024), date("2024-12-31"));
+        assert_eq!(
+            Quarter::Q1.first_day(u16::MAX),
+            NaiveDate::from_ymd_opt(65535, 1, 1).unwrap()
+        );
+        assert_eq!(Quarter::Q4.last_day(0), date("0000-12-31"));
     }
 
     #[test]
@@ -625,7 +842,7 @@ mod tests {
     fn test_list_institutional_params_serialize() {
         let params = ListInstitutionalParams::builder()
             .cusip("037833100")
-            .report_period("2024-09-30")
+            .report_quarter(2024, Quarter::Q3)
             .build();
 
         let json = serde_json::to_value(&params).unwrap();
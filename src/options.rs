@@ -0,0 +1,157 @@
+//! Per-request options.
+//!
+//! This module provides [`RequestOptions`], which override client-wide
+//! settings for the calls of a single resource.
+
+use std::future::Future;
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use tokio_util::sync::CancellationToken;
+
+use crate::error::{Error, Result};
+
+/// Header carrying the correlation ID of a request.
+pub const CORRELATION_ID_HEADER: &str = "X-Correlation-Id";
+
+/// Options applied to every request of a resource.
+///
+/// Pass them with `with_options` on a resource, e.g.
+/// `client.filings().with_options(options)`. They also apply to every page
+/// requested by the resource's streams.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use earningsfeed::{CancellationToken, ListFilingsParams, RequestOptions};
+/// use std::time::Duration;
+///
+/// let token = CancellationToken::new();
+/// let options = RequestOptions::new()
+///     .timeout(Duration::from_secs(120))
+///     .correlation_id("backfill-2024")
+///     .cancellation_token(token.clone());
+///
+/// let filings = client.filings().with_options(options);
+/// let mut stream = filings.iter(ListFilingsParams::default());
+/// // Later, from anywhere: stops the request in flight and the stream.
+/// token.cancel();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    /// Timeout overriding the client-wide timeout.
+    pub timeout: Option<Duration>,
+    /// Headers sent in addition to the client's headers.
+    pub headers: HeaderMap,
+    /// Correlation ID sent in the `X-Correlation-Id` header.
+    pub correlation_id: Option<String>,
+    /// Token aborting requests and streams when cancelled.
+    pub cancellation_token: Option<CancellationToken>,
+}
+
+impl RequestOptions {
+    /// Create empty options.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Override the client-wide request timeout.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Add a request header.
+    ///
+    /// Invalid header names or values are ignored.
+    #[must_use]
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            self.headers.insert(name, value);
+        }
+        self
+    }
+
+    /// Set the correlation ID sent with every request.
+    #[must_use]
+    pub fn correlation_id(mut self, id: impl Into<String>) -> Self {
+        self.correlation_id = Some(id.into());
+        self
+    }
+
+    /// Abort requests and streams when the token is cancelled.
+    #[must_use]
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// All headers to send, including the correlation ID.
+    pub(crate) fn all_headers(&self) -> HeaderMap {
+        let mut headers = self.headers.clone();
+        if let Some(value) = self
+            .correlation_id
+            .as_deref()
+            .and_then(|id| HeaderValue::from_str(id).ok())
+        {
+            headers.insert(CORRELATION_ID_HEADER, value);
+        }
+        headers
+    }
+
+    /// Whether requests with these options may share a response with
+    /// requests made without them.
+    pub(crate) fn is_shareable(&self) -> bool {
+        self.timeout.is_none() && self.headers.is_empty() && self.correlation_id.is_none()
+    }
+
+    /// Run a future, failing with [`Error::Cancelled`] if the token is
+    /// cancelled first.
+    pub(crate) async fn run<T, F>(&self, future: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        match &self.cancellation_token {
+            Some(token) => token
+                .run_until_cancelled(future)
+                .await
+                .unwrap_or(Err(Error::Cancelled)),
+            None => future.await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_headers_include_correlation_id() {
+        let options = RequestOptions::new()
+            .header("X-Team", "research")
+            .header("bad header", "ignored")
+            .correlation_id("job-42");
+        let headers = options.all_headers();
+
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["x-team"], "research");
+        assert_eq!(headers[CORRELATION_ID_HEADER], "job-42");
+        assert!(!options.is_shareable());
+        assert!(RequestOptions::new().is_shareable());
+    }
+
+    #[tokio::test]
+    async fn test_run_stops_when_cancelled() {
+        let token = CancellationToken::new();
+        let options = RequestOptions::new().cancellation_token(token.clone());
+
+        token.cancel();
+        let result = options.run(std::future::pending::<Result<()>>()).await;
+        assert!(matches!(result, Err(Error::Cancelled)));
+    }
+}
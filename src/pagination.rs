@@ -0,0 +1,683 @@
+//! Cursor pagination over list endpoints.
+//!
+//! This module provides the [`Paginated`] trait, implemented by the
+//! parameters of every list endpoint, and [`Paginator`], which follows
+//! their cursors to stream pages or items. Progress is captured as a
+//! [`PaginationCheckpoint`] that can be stored and resumed from.
+
+use std::collections::HashSet;
+
+use async_stream::try_stream;
+use futures::future::{BoxFuture, FutureExt};
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::client::EarningsFeed;
+use crate::error::{Error, Result};
+use crate::models::PaginatedResponse;
+use crate::options::RequestOptions;
+use crate::response::Response;
+use crate::trace::PaginationSpan;
+
+/// Parameters of a cursor-paginated endpoint.
+pub trait Paginated {
+    /// Cursor of the page to fetch, or `None` for the first page.
+    fn cursor(&self) -> Option<&str>;
+
+    /// Set the cursor of the page to fetch.
+    fn set_cursor(&mut self, cursor: Option<String>);
+}
+
+/// Position of a [`Paginator`] in its endpoint's results.
+///
+/// Checkpoints are serializable, so they can be saved after every page
+/// (see [`Paginator::on_page`]) and passed to [`Paginator::resume`] after a
+/// restart to continue where pagination left off.
+///
+/// # Example
+///
+/// ```rust
+/// use earningsfeed::PaginationCheckpoint;
+///
+/// let checkpoint = PaginationCheckpoint {
+///     cursor: Some("eyJpZCI6MTIzfQ".to_string()),
+///     page: 42,
+/// };
+/// let saved = serde_json::to_string(&checkpoint).unwrap();
+/// let restored: PaginationCheckpoint = serde_json::from_str(&saved).unwrap();
+/// assert_eq!(restored, checkpoint);
+/// assert!(!restored.is_complete());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaginationCheckpoint {
+    /// Cursor of the next page to fetch, or `None` for the first page (or,
+    /// once pages were fetched, when there are no more).
+    pub cursor: Option<String>,
+    /// Number of pages fetched so far.
+    pub page: u64,
+}
+
+impl PaginationCheckpoint {
+    /// Whether every page has been fetched.
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.page > 0 && self.cursor.is_none()
+    }
+}
+
+/// Fetches the page at the given cursor.
+type FetchPage<T> =
+    Box<dyn FnMut(Option<String>) -> BoxFuture<'static, Result<PaginatedResponse<T>>> + Send>;
+
+/// Called with the checkpoint after each page.
+type PageHook<'a> = Box<dyn FnMut(&PaginationCheckpoint) + Send + 'a>;
+
+/// Follows the cursors of a list endpoint.
+///
+/// Obtain one from the `paginate` method of a resource, e.g.
+/// `client.filings().paginate(params)`, optionally limit it with
+/// [`take_pages`](Self::take_pages) and [`max_items`](Self::max_items), then
+/// consume it as a stream of [`pages`](Self::pages) or
+/// [`items`](Self::items), or with [`collect_all`](Self::collect_all).
+///
+/// Pages are fetched lazily, starting at the cursor of the parameters, and
+/// pagination ends when the server reports no more results. If the server
+/// returns a cursor that was already followed, the stream ends with
+/// [`Error::RepeatedCursor`] instead of looping forever. With
+/// [`prefetch`](Self::prefetch), the next pages are fetched while the
+/// current one is consumed.
+///
+/// Long-running pagination can be made resumable by saving the checkpoint
+/// passed to [`on_page`](Self::on_page) and, after a restart, continuing
+/// from it with [`resume`](Self::resume).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let params = ListFilingsParams::builder().ticker("AAPL").build();
+///
+/// let filings = client
+///     .filings()
+///     .paginate(params)
+///     .max_items(500)
+///     .collect_all()
+///     .await?;
+/// ```
+///
+/// Resuming a backfill:
+///
+/// ```rust,ignore
+/// let checkpoint = job_store.load()?.unwrap_or_default();
+///
+/// let mut holdings = pin!(client
+///     .institutional()
+///     .paginate(params)
+///     .resume(checkpoint)
+///     .on_page(|checkpoint| job_store.save(checkpoint))
+///     .items());
+/// while let Some(holding) = holdings.next().await {
+///     process(holding?);
+/// }
+/// ```
+pub struct Paginator<'a, T> {
+    client: EarningsFeed,
+    path: &'static str,
+    cursor: Option<String>,
+    page: u64,
+    fetch: FetchPage<T>,
+    on_page: Option<PageHook<'a>>,
+    max_pages: Option<usize>,
+    max_items: Option<usize>,
+    prefetch: usize,
+}
+
+impl<'a, T: Send + 'static> Paginator<'a, T> {
+    /// Create a paginator listing the endpoint at `path` with `params`.
+    pub(crate) fn new<P>(
+        client: &'a EarningsFeed,
+        path: &'static str,
+        params: P,
+        options: RequestOptions,
+    ) -> Self
+    where
+        P: Paginated + Serialize + Clone + Send + Sync + 'static,
+        T: DeserializeOwned,
+    {
+        let cursor = params.cursor().map(str::to_string);
+        let mut params = params;
+        let fetch_client = client.clone();
+        Self {
+            client: client.clone(),
+            path,
+            cursor,
+            page: 0,
+            fetch: Box::new(move |cursor| {
+                params.set_cursor(cursor);
+                let client = fetch_client.clone();
+                let params = params.clone();
+                let options = options.clone();
+                async move {
+                    client
+                        .get_with_options(path, Some(&params), &options)
+                        .await
+                        .map(Response::into_inner)
+                }
+                .boxed()
+            }),
+            on_page: None,
+            max_pages: None,
+            max_items: None,
+            prefetch: 0,
+        }
+    }
+
+    /// Continue from a checkpoint instead of the cursor of the parameters.
+    ///
+    /// Resuming from a complete checkpoint fetches no pages.
+    #[must_use]
+    pub fn resume(mut self, checkpoint: PaginationCheckpoint) -> Self {
+        self.cursor = checkpoint.cursor;
+        self.page = checkpoint.page;
+        self
+    }
+
+    /// Call `f` with the checkpoint after each page.
+    ///
+    /// The callback runs once the page has been consumed, i.e. when the
+    /// next page is requested from the stream, so a saved checkpoint never
+    /// skips unprocessed items. A page truncated by
+    /// [`max_items`](Self::max_items) is not checkpointed.
+    #[must_use]
+    pub fn on_page(mut self, f: impl FnMut(&PaginationCheckpoint) + Send + 'a) -> Self {
+        self.on_page = Some(Box::new(f));
+        self
+    }
+
+    /// The checkpoint pagination starts from.
+    #[must_use]
+    pub fn checkpoint(&self) -> PaginationCheckpoint {
+        PaginationCheckpoint {
+            cursor: self.cursor.clone(),
+            page: self.page,
+        }
+    }
+
+    /// Stop after fetching at most `pages` pages.
+    #[must_use]
+    pub fn take_pages(mut self, pages: usize) -> Self {
+        self.max_pages = Some(pages);
+        self
+    }
+
+    /// Stop after yielding at most `items` items.
+    ///
+    /// The page reaching the limit is truncated, and no further pages are
+    /// fetched.
+    #[must_use]
+    pub fn max_items(mut self, items: usize) -> Self {
+        self.max_items = Some(items);
+        self
+    }
+
+    /// Fetch up to `pages` pages ahead of the page being consumed.
+    ///
+    /// Pages are fetched by a background Tokio task while the current page
+    /// is consumed, so slow consumers do not leave the network idle. Pages
+    /// are still requested one at a time, through the client, so its rate
+    /// limiter and retry policy apply. The task stops when the stream is
+    /// dropped. `0`, the default, requests each page only once the previous
+    /// one has been consumed.
+    #[must_use]
+    pub fn prefetch(mut self, pages: usize) -> Self {
+        self.prefetch = pages;
+        self
+    }
+
+    /// Stream the pages.
+    pub fn pages(self) -> impl Stream<Item = Result<PaginatedResponse<T>>> + 'a {
+        let complete = self.checkpoint().is_complete();
+        let Self {
+            client,
+            path,
+            cursor,
+            page,
+            fetch,
+            mut on_page,
+            max_pages,
+            max_items,
+            prefetch,
+        } = self;
+
+        try_stream! {
+            if complete {
+                return;
+            }
+
+            let fetched = fetch_pages(client, path, cursor, page, fetch, max_pages, max_items);
+            let mut fetched = match prefetch {
+                0 => fetched.boxed(),
+                depth => prefetch_pages(fetched, depth).boxed(),
+            };
+
+            while let Some(fetched_page) = fetched.next().await {
+                let FetchedPage {
+                    response,
+                    checkpoint,
+                    truncated,
+                    repeated,
+                } = fetched_page?;
+                yield response;
+
+                if repeated {
+                    let cursor = checkpoint.cursor.clone().unwrap_or_default();
+                    Err(Error::RepeatedCursor {
+                        path: path.to_string(),
+                        cursor,
+                    })?;
+                }
+                if let (false, Some(on_page)) = (truncated, &mut on_page) {
+                    on_page(&checkpoint);
+                }
+            }
+        }
+    }
+
+    /// Stream the items of every page.
+    pub fn items(self) -> impl Stream<Item = Result<T>> + 'a {
+        self.pages()
+            .map_ok(|page| stream::iter(page.items).map(Ok))
+            .try_flatten()
+    }
+
+    /// Collect the items of every page.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error encountered while fetching pages.
+    pub async fn collect_all(self) -> Result<Vec<T>> {
+        self.items().try_collect().await
+    }
+}
+
+impl<T> std::fmt::Debug for Paginator<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Paginator")
+            .field("path", &self.path)
+            .field("cursor", &self.cursor)
+            .field("page", &self.page)
+            .field("max_pages", &self.max_pages)
+            .field("max_items", &self.max_items)
+            .field("prefetch", &self.prefetch)
+            .finish()
+    }
+}
+
+/// A fetched page, with the checkpoint after it.
+struct FetchedPage<T> {
+    response: PaginatedResponse<T>,
+    checkpoint: PaginationCheckpoint,
+    /// Whether the page was truncated by `max_items`.
+    truncated: bool,
+    /// Whether the cursor after the page was already followed.
+    repeated: bool,
+}
+
+/// Fetch pages one after the other.
+///
+/// Ends after the last page, at the limits, after an error, or with a page
+/// followed by a repeated cursor.
+fn fetch_pages<T: Send + 'static>(
+    client: EarningsFeed,
+    path: &'static str,
+    mut cursor: Option<String>,
+    mut page: u64,
+    mut fetch: FetchPage<T>,
+    max_pages: Option<usize>,
+    max_items: Option<usize>,
+) -> impl Stream<Item = Result<FetchedPage<T>>> + Send + 'static {
+    try_stream! {
+        let mut span = PaginationSpan::new(path);
+        let mut seen: HashSet<String> = cursor.iter().cloned().collect();
+        let mut fetched = 0;
+        let mut items = 0;
+
+        loop {
+            if max_pages.is_some_and(|max| fetched >= max)
+                || max_items.is_some_and(|max| items >= max)
+            {
+                break;
+            }
+
+            let mut response = span.run(fetch(cursor.take())).await?;
+            fetched += 1;
+            page += 1;
+            let len = response.items.len();
+            if let Some(max) = max_items {
+                response.items.truncate(max - items);
+            }
+            let truncated = response.items.len() < len;
+            items += response.items.len();
+            span.record_page(response.items.len());
+            client.record_page(path);
+
+            cursor = match &response.next_cursor {
+                Some(next) if response.has_more => Some(next.clone()),
+                _ => None,
+            };
+            let repeated = cursor.as_ref().is_some_and(|next| !seen.insert(next.clone()));
+            let done = cursor.is_none() || truncated || repeated;
+            yield FetchedPage {
+                response,
+                checkpoint: PaginationCheckpoint {
+                    cursor: cursor.clone(),
+                    page,
+                },
+                truncated,
+                repeated,
+            };
+
+            if done {
+                break;
+            }
+        }
+    }
+}
+
+/// Drive `pages` in a background task, at most `depth` pages ahead.
+fn prefetch_pages<T: Send + 'static>(
+    pages: impl Stream<Item = Result<FetchedPage<T>>> + Send + 'static,
+    depth: usize,
+) -> impl Stream<Item = Result<FetchedPage<T>>> + Send + 'static {
+    let (tx, mut rx) = tokio::sync::mpsc::channel(depth);
+    let task = tokio::spawn(async move {
+        let mut pages = Box::pin(pages);
+        // A slot is reserved before each request, so buffered and in-flight
+        // pages never exceed `depth`.
+        while let Ok(permit) = tx.reserve().await {
+            match pages.next().await {
+                Some(page) => permit.send(page),
+                None => break,
+            }
+        }
+    });
+
+    let task = AbortOnDrop(task);
+    stream::poll_fn(move |cx| {
+        let _ = &task;
+        rx.poll_recv(cx)
+    })
+}
+
+/// Aborts a task when dropped.
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ListFilingsParams, SearchCompaniesParams};
+    use crate::rate_limit::RateLimiterConfig;
+    use std::pin::pin;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn setup_client(mock_server: &MockServer) -> EarningsFeed {
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .build()
+            .unwrap();
+        EarningsFeed::with_config(config).unwrap()
+    }
+
+    fn search_page(ciks: &[u64], next_cursor: Option<&str>) -> ResponseTemplate {
+        let items: Vec<_> = ciks
+            .iter()
+            .map(|cik| serde_json::json!({ "cik": cik, "name": format!("Company {}", cik) }))
+            .collect();
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": items,
+            "nextCursor": next_cursor,
+            "hasMore": next_cursor.is_some()
+        }))
+    }
+
+    /// Wait until the server has received at least `count` requests,
+    /// returning the number received.
+    async fn wait_for_requests(mock_server: &MockServer, count: usize) -> usize {
+        let received = async {
+            loop {
+                let received = mock_server.received_requests().await.unwrap().len();
+                if received >= count {
+                    return received;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(10), received)
+            .await
+            .expect("timed out waiting for requests")
+    }
+
+    async fn mount_search_pages(mock_server: &MockServer) {
+        Mock::given(method("GET"))
+            .and(path("/api/v1/companies/search"))
+            .and(query_param_is_missing("cursor"))
+            .respond_with(search_page(&[1, 2], Some("c2")))
+            .mount(mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/companies/search"))
+            .and(query_param("cursor", "c2"))
+            .respond_with(search_page(&[3, 4], Some("c3")))
+            .mount(mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/companies/search"))
+            .and(query_param("cursor", "c3"))
+            .respond_with(search_page(&[5], None))
+            .mount(mock_server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_pages_and_limits() {
+        let mock_server = MockServer::start().await;
+        mount_search_pages(&mock_server).await;
+        let client = setup_client(&mock_server).await;
+        let companies = client.companies();
+        let params = SearchCompaniesParams::builder().q("co").build();
+
+        let pages: Vec<_> = companies
+            .paginate_search(params.clone())
+            .pages()
+            .try_collect()
+            .await
+            .unwrap();
+        let sizes: Vec<_> = pages.iter().map(|page| page.items.len()).collect();
+        assert_eq!(sizes, vec![2, 2, 1]);
+
+        let all = companies
+            .paginate_search(params.clone())
+            .collect_all()
+            .await
+            .unwrap();
+        let ciks: Vec<_> = all.iter().map(|c| c.cik).collect();
+        assert_eq!(ciks, vec![1, 2, 3, 4, 5]);
+
+        let first_two = companies
+            .paginate_search(params.clone())
+            .take_pages(2)
+            .collect_all()
+            .await
+            .unwrap();
+        assert_eq!(first_two.len(), 4);
+
+        let requests = mock_server.received_requests().await.unwrap().len();
+        let three = companies
+            .paginate_search(params.clone())
+            .max_items(3)
+            .collect_all()
+            .await
+            .unwrap();
+        let ciks: Vec<_> = three.iter().map(|c| c.cik).collect();
+        assert_eq!(ciks, vec![1, 2, 3]);
+        assert_eq!(
+            mock_server.received_requests().await.unwrap().len(),
+            requests + 2
+        );
+
+        // Pagination starts at the cursor of the parameters.
+        let mut resumed = params;
+        resumed.cursor = Some("c3".to_string());
+        let last = companies
+            .paginate_search(resumed)
+            .collect_all()
+            .await
+            .unwrap();
+        assert_eq!(last.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_and_resume() {
+        let mock_server = MockServer::start().await;
+        mount_search_pages(&mock_server).await;
+        let client = setup_client(&mock_server).await;
+        let companies = client.companies();
+        let params = SearchCompaniesParams::builder().q("co").build();
+
+        let saved = Arc::new(Mutex::new(Vec::new()));
+        let checkpoints = Arc::clone(&saved);
+        let first_run = companies
+            .paginate_search(params.clone())
+            .take_pages(2)
+            .on_page(move |checkpoint| checkpoints.lock().unwrap().push(checkpoint.clone()))
+            .collect_all()
+            .await
+            .unwrap();
+        assert_eq!(first_run.len(), 4);
+
+        let checkpoint = |cursor: Option<&str>, page| PaginationCheckpoint {
+            cursor: cursor.map(str::to_string),
+            page,
+        };
+        assert_eq!(
+            *saved.lock().unwrap(),
+            vec![checkpoint(Some("c2"), 1), checkpoint(Some("c3"), 2)]
+        );
+
+        // Restart from the stored checkpoint.
+        let stored = serde_json::to_string(saved.lock().unwrap().last().unwrap()).unwrap();
+        let resumed: PaginationCheckpoint = serde_json::from_str(&stored).unwrap();
+        let checkpoints = Arc::clone(&saved);
+        let rest = companies
+            .paginate_search(params.clone())
+            .resume(resumed)
+            .on_page(move |checkpoint| checkpoints.lock().unwrap().push(checkpoint.clone()))
+            .collect_all()
+            .await
+            .unwrap();
+        let ciks: Vec<_> = rest.iter().map(|c| c.cik).collect();
+        assert_eq!(ciks, vec![5]);
+
+        let last = saved.lock().unwrap().last().cloned().unwrap();
+        assert_eq!(last, checkpoint(None, 3));
+        assert!(last.is_complete());
+
+        // A complete checkpoint fetches nothing.
+        let requests = mock_server.received_requests().await.unwrap().len();
+        let paginator = companies.paginate_search(params).resume(last);
+        assert!(paginator.collect_all().await.unwrap().is_empty());
+        assert_eq!(
+            mock_server.received_requests().await.unwrap().len(),
+            requests
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_fetches_ahead_of_consumer() {
+        let mock_server = MockServer::start().await;
+        mount_search_pages(&mock_server).await;
+        let client = setup_client(&mock_server).await;
+        let params = SearchCompaniesParams::builder().q("co").build();
+        let requests = || async { mock_server.received_requests().await.unwrap().len() };
+
+        let paginator = client.companies().paginate_search(params.clone());
+        let mut pages = pin!(paginator.prefetch(1).pages());
+        assert_eq!(pages.next().await.unwrap().unwrap().items.len(), 2);
+
+        // The second page is fetched while the first is consumed, the third
+        // only once the second is taken.
+        assert_eq!(wait_for_requests(&mock_server, 2).await, 2);
+        let rest: Vec<_> = pages.try_collect().await.unwrap();
+        assert_eq!(rest.len(), 2);
+        assert_eq!(requests().await, 3);
+
+        let paginator = client.companies().paginate_search(params);
+        let mut items = pin!(paginator.prefetch(5).items());
+        assert_eq!(items.next().await.unwrap().unwrap().cik, 1);
+        assert_eq!(wait_for_requests(&mock_server, 6).await, 6);
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_respects_rate_limiter() {
+        let mock_server = MockServer::start().await;
+        mount_search_pages(&mock_server).await;
+        let config = EarningsFeed::builder()
+            .api_key("test_key")
+            .base_url(mock_server.uri())
+            .rate_limiter(RateLimiterConfig::new(20.0, 1))
+            .build()
+            .unwrap();
+        let client = EarningsFeed::with_config(config).unwrap();
+
+        let start = std::time::Instant::now();
+        let companies = client
+            .companies()
+            .paginate_search(SearchCompaniesParams::builder().q("co").build())
+            .prefetch(3)
+            .collect_all()
+            .await
+            .unwrap();
+        assert_eq!(companies.len(), 5);
+        // Three requests at 20 per second, with a burst of one.
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+
+    #[tokio::test]
+    async fn test_repeated_cursor_ends_with_error() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [],
+                "nextCursor": "stuck",
+                "hasMore": true
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let client = setup_client(&mock_server).await;
+        let params = ListFilingsParams::builder().ticker("AAPL").build();
+        let results: Vec<_> = client.filings().paginate(params).pages().collect().await;
+
+        assert_eq!(results.len(), 3);
+        assert!(results[..2].iter().all(|page| page.is_ok()));
+        match &results[2] {
+            Err(Error::RepeatedCursor { path, cursor }) => {
+                assert_eq!(path, "/api/v1/filings");
+                assert_eq!(cursor, "stuck");
+            }
+            other => panic!("expected repeated cursor, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,805 @@
+//! Loading client configuration from environment variables and config files.
+//!
+//! Every setting has a dotted key (e.g. `retry.max_attempts`). In config
+//! files, keys are nested tables or objects inside a named profile; as
+//! environment variables, they are upper-cased, prefixed with
+//! `EARNINGSFEED_` and use underscores (e.g. `EARNINGSFEED_RETRY_MAX_ATTEMPTS`).
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::cache::{CacheConfig, CacheResource};
+use crate::cassette::{CassetteConfig, CassetteMode};
+use crate::circuit_breaker::CircuitBreakerConfig;
+use crate::config::ClientConfigBuilder;
+use crate::credentials::{ApiKey, FileCredentials};
+use crate::disk_cache::DiskCacheConfig;
+use crate::error::{Error, Result};
+use crate::rate_limit::RateLimiterConfig;
+use crate::retry::RetryPolicy;
+
+/// Environment variable naming a config file to load before other variables.
+pub const ENV_CONFIG_FILE: &str = "EARNINGSFEED_CONFIG";
+
+/// Environment variable selecting the profile of the config file.
+pub const ENV_PROFILE: &str = "EARNINGSFEED_PROFILE";
+
+/// Prefix of the environment variable for each setting.
+const ENV_PREFIX: &str = "EARNINGSFEED_";
+
+/// Every supported setting.
+///
+/// Must cover every [`ClientConfigBuilder`] option that can be expressed
+/// as text; `apply` fails on keys missing from this list.
+const SETTINGS: &[&str] = &[
+    "api_key",
+    "api_key_file",
+    "base_url",
+    "timeout",
+    "retry.max_attempts",
+    "retry.base_delay",
+    "retry.max_delay",
+    "retry.max_rate_limit_wait",
+    "retry.jitter",
+    "retry.statuses",
+    "retry.transport_errors",
+    "retry.enabled",
+    "rate_limiter.requests_per_second",
+    "rate_limiter.burst",
+    "rate_limiter.adaptive",
+    "rate_limiter.enabled",
+    "circuit_breaker.failure_threshold",
+    "circuit_breaker.cool_down",
+    "circuit_breaker.half_open_probes",
+    "circuit_breaker.enabled",
+    "cache.max_entries",
+    "cache.ttl.filings",
+    "cache.ttl.insider",
+    "cache.ttl.institutional",
+    "cache.ttl.companies",
+    "cache.provisional_ttl",
+    "cache.final_filings",
+    "cache.lists",
+    "cache.enabled",
+    "disk_cache.dir",
+    "disk_cache.max_bytes",
+    "disk_cache.offline",
+    "disk_cache.enabled",
+    "cassette.path",
+    "cassette.mode",
+    "cassette.enabled",
+    "single_flight",
+    "metrics",
+    "transport.proxy",
+    "transport.connect_timeout",
+    "transport.pool_idle_timeout",
+    "transport.pool_max_idle_per_host",
+    "transport.gzip",
+    "transport.brotli",
+    "transport.http2_prior_knowledge",
+    "transport.root_certificates",
+];
+
+/// Apply settings from environment variables, read with `var`.
+///
+/// If [`ENV_CONFIG_FILE`] is set, the selected profile of that file is
+/// applied first, so individual variables override it.
+pub(crate) fn apply_env<F>(mut builder: ClientConfigBuilder, var: F) -> Result<ClientConfigBuilder>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    if let Some(path) = var(ENV_CONFIG_FILE) {
+        let profile = var(ENV_PROFILE);
+        builder = apply_file(builder, Path::new(&path), profile.as_deref())?;
+    }
+
+    let settings = SETTINGS
+        .iter()
+        .filter_map(|key| var(&env_name(key)).map(|value| (key.to_string(), value)));
+    for (key, value) in settings {
+        builder = apply(builder, &key, &value, &env_name(&key))?;
+    }
+    check(builder)
+}
+
+/// Apply the settings of a profile in a config file.
+///
+/// If `profile` is `None`, the file's `default_profile` is used.
+pub(crate) fn apply_file(
+    mut builder: ClientConfigBuilder,
+    path: &Path,
+    profile: Option<&str>,
+) -> Result<ClientConfigBuilder> {
+    let document = read_file(path)?;
+
+    let profile = match profile {
+        Some(profile) => profile.to_string(),
+        None => document["default_profile"]
+            .as_str()
+            .map(String::from)
+            .ok_or_else(|| {
+                Error::Config(format!(
+                    "no profile selected for {}: set {} or default_profile",
+                    path.display(),
+                    ENV_PROFILE
+                ))
+            })?,
+    };
+
+    let Some(settings) = document["profiles"].get(&profile) else {
+        let available = document["profiles"]
+            .as_object()
+            .map(|profiles| profiles.keys().cloned().collect::<Vec<_>>().join(", "))
+            .unwrap_or_default();
+        return Err(Error::Config(format!(
+            "profile '{}' not found in {} (available: {})",
+            profile,
+            path.display(),
+            available
+        )));
+    };
+
+    let mut flat = Vec::new();
+    flatten("", settings, &mut flat);
+    // `enabled` keys apply last so they win over other keys of their section.
+    flat.sort_by_key(|(key, _)| key.ends_with(".enabled"));
+
+    for (key, value) in flat {
+        let source = format!("{} in profile '{}'", key, profile);
+        builder = apply(builder, &key, &value, &source)?;
+    }
+    check(builder)
+}
+
+/// Read a JSON or (with the `toml` feature) TOML config file.
+fn read_file(path: &Path) -> Result<serde_json::Value> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| Error::Config(format!("cannot read {}: {}", path.display(), e)))?;
+    let invalid = |e: &dyn std::fmt::Display| {
+        Error::Config(format!("invalid config file {}: {}", path.display(), e))
+    };
+
+    if path.extension().and_then(|e| e.to_str()) == Some("toml") {
+        #[cfg(feature = "toml")]
+        {
+            let value: toml::Value = toml::from_str(&contents).map_err(|e| invalid(&e))?;
+            return serde_json::to_value(value).map_err(|e| invalid(&e));
+        }
+        #[cfg(not(feature = "toml"))]
+        return Err(Error::Config(format!(
+            "cannot read {}: TOML config files require the `toml` feature",
+            path.display()
+        )));
+    }
+
+    serde_json::from_str(&contents).map_err(|e| invalid(&e))
+}
+
+/// Flatten nested tables into dotted keys with text values.
+fn flatten(prefix: &str, value: &serde_json::Value, out: &mut Vec<(String, String)>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&key, value, out);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(text).collect();
+            out.push((prefix.to_string(), items.join(",")));
+        }
+        value => out.push((prefix.to_string(), text(value))),
+    }
+}
+
+fn text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Environment variable name for a setting.
+fn env_name(key: &str) -> String {
+    format!(
+        "{}{}",
+        ENV_PREFIX,
+        key.replace('.', "_").to_ascii_uppercase()
+    )
+}
+
+/// Apply a single setting to the builder.
+///
+/// `source` names where the value came from, for error messages.
+fn apply(
+    mut b: ClientConfigBuilder,
+    key: &str,
+    value: &str,
+    source: &str,
+) -> Result<ClientConfigBuilder> {
+    let value = value.trim();
+    match key {
+        "api_key" => b.api_key = Some(ApiKey::new(value)),
+        "api_key_file" => b.credentials = Some(Arc::new(FileCredentials::new(value))),
+        "base_url" => b.base_url = Some(value.to_string()),
+        "timeout" => b.timeout = Some(parse_duration(value, source)?),
+
+        "retry.enabled" => {
+            if !parse_bool(value, source)? {
+                b.retry = None;
+            } else if b.retry.is_none() {
+                b.retry = Some(RetryPolicy::default());
+            }
+        }
+        "retry.max_attempts" => retry(&mut b).max_attempts = parse::<u32>(value, source)?.max(1),
+        "retry.base_delay" => retry(&mut b).base_delay = parse_duration(value, source)?,
+        "retry.max_delay" => retry(&mut b).max_delay = parse_duration(value, source)?,
+        "retry.max_rate_limit_wait" => {
+            retry(&mut b).max_rate_limit_wait = parse_duration(value, source)?;
+        }
+        "retry.jitter" => {
+            let jitter: f64 = parse(value, source)?;
+            if !(0.0..=1.0).contains(&jitter) {
+                return Err(invalid(source, value, "expected a number between 0 and 1"));
+            }
+            retry(&mut b).jitter = jitter;
+        }
+        "retry.statuses" => {
+            retry(&mut b).retry_statuses = value
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| {
+                    s.parse().map_err(|_| {
+                        invalid(source, value, "expected comma-separated status codes")
+                    })
+                })
+                .collect::<Result<_>>()?;
+        }
+        "retry.transport_errors" => {
+            retry(&mut b).retry_transport_errors = parse_bool(value, source)?;
+        }
+
+        "rate_limiter.enabled" => {
+            if !parse_bool(value, source)? {
+                b.rate_limiter = None;
+            }
+        }
+        "rate_limiter.requests_per_second" => {
+            rate_limiter(&mut b).requests_per_second = parse(value, source)?;
+        }
+        "rate_limiter.burst" => rate_limiter(&mut b).burst = parse(value, source)?,
+        "rate_limiter.adaptive" => rate_limiter(&mut b).adaptive = parse_bool(value, source)?,
+
+        "circuit_breaker.enabled" => {
+            if !parse_bool(value, source)? {
+                b.circuit_breaker = None;
+            } else if b.circuit_breaker.is_none() {
+                b.circuit_breaker = Some(CircuitBreakerConfig::default());
+            }
+        }
+        "circuit_breaker.failure_threshold" => {
+            circuit_breaker(&mut b).failure_threshold = parse(value, source)?;
+        }
+        "circuit_breaker.cool_down" => {
+            circuit_breaker(&mut b).cool_down = parse_duration(value, source)?;
+        }
+        "circuit_breaker.half_open_probes" => {
+            circuit_breaker(&mut b).half_open_probes = parse(value, source)?;
+        }
+
+        "cache.enabled" => {
+            if !parse_bool(value, source)? {
+                b.cache = None;
+            } else if b.cache.is_none() {
+                b.cache = Some(CacheConfig::default());
+            }
+        }
+        "cache.max_entries" => cache(&mut b).max_entries = parse(value, source)?,
+        "cache.ttl.filings" => set_ttl(&mut b, CacheResource::Filings, value, source)?,
+        "cache.ttl.insider" => set_ttl(&mut b, CacheResource::Insider, value, source)?,
+        "cache.ttl.institutional" => set_ttl(&mut b, CacheResource::Institutional, value, source)?,
+        "cache.ttl.companies" => set_ttl(&mut b, CacheResource::Companies, value, source)?,
+        "cache.provisional_ttl" => cache(&mut b).provisional_ttl = parse_duration(value, source)?,
+        "cache.final_filings" => cache(&mut b).cache_final_filings = parse_bool(value, source)?,
+        "cache.lists" => cache(&mut b).cache_lists = parse_bool(value, source)?,
+
+        "disk_cache.enabled" => {
+            if !parse_bool(value, source)? {
+                b.disk_cache = None;
+            }
+        }
+        "disk_cache.dir" => disk_cache(&mut b).dir = value.into(),
+        "disk_cache.max_bytes" => disk_cache(&mut b).max_bytes = parse(value, source)?,
+        "disk_cache.offline" => disk_cache(&mut b).offline = parse_bool(value, source)?,
+
+        "cassette.enabled" => {
+            if !parse_bool(value, source)? {
+                b.cassette = None;
+            }
+        }
+        "cassette.path" => cassette(&mut b).path = value.into(),
+        "cassette.mode" => {
+            cassette(&mut b).mode = match value {
+                "record" => CassetteMode::Record,
+                "replay" => CassetteMode::Replay,
+                _ => return Err(invalid(source, value, "expected record or replay")),
+            };
+        }
+
+        "single_flight" => b.single_flight = Some(parse_bool(value, source)?),
+        "metrics" => b.metrics = parse_bool(value, source)?,
+
+        "transport.proxy" => {
+            let proxy = reqwest::Proxy::all(value)
+                .map_err(|_| invalid(source, value, "expected a proxy URL"))?;
+            b.transport.proxy = Some(proxy);
+        }
+        "transport.connect_timeout" => {
+            b.transport.connect_timeout = Some(parse_duration(value, source)?);
+        }
+        "transport.pool_idle_timeout" => {
+            b.transport.pool_idle_timeout = Some(parse_duration(value, source)?);
+        }
+        "transport.pool_max_idle_per_host" => {
+            b.transport.pool_max_idle_per_host = Some(parse(value, source)?);
+        }
+        "transport.gzip" => b.transport.gzip = Some(parse_bool(value, source)?),
+        "transport.brotli" => b.transport.brotli = Some(parse_bool(value, source)?),
+        "transport.http2_prior_knowledge" => {
+            b.transport.http2_prior_knowledge = parse_bool(value, source)?;
+        }
+        "transport.root_certificates" => {
+            for path in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                let certificates = std::fs::read(path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|pem| {
+                        reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| e.to_string())
+                    })
+                    .map_err(|e| invalid(source, path, &format!("expected PEM files: {}", e)))?;
+                b.transport.root_certificates.extend(certificates);
+            }
+        }
+
+        _ => {
+            debug_assert!(!SETTINGS.contains(&key), "unhandled setting {}", key);
+            return Err(Error::Config(format!("unknown setting {}", source)));
+        }
+    }
+    Ok(b)
+}
+
+/// Check that partially configured sections are usable.
+fn check(builder: ClientConfigBuilder) -> Result<ClientConfigBuilder> {
+    if let Some(disk_cache) = &builder.disk_cache {
+        if disk_cache.dir.as_os_str().is_empty() {
+            return Err(Error::Config(
+                "disk_cache.dir is required to enable the disk cache".into(),
+            ));
+        }
+    }
+    if let Some(cassette) = &builder.cassette {
+        if cassette.path.as_os_str().is_empty() {
+            return Err(Error::Config(
+                "cassette.path is required to enable the cassette".into(),
+            ));
+        }
+    }
+    Ok(builder)
+}
+
+fn retry(builder: &mut ClientConfigBuilder) -> &mut RetryPolicy {
+    builder.retry.get_or_insert_with(RetryPolicy::default)
+}
+
+fn rate_limiter(builder: &mut ClientConfigBuilder) -> &mut RateLimiterConfig {
+    // Missing values fail validation when the configuration is built.
+    builder
+        .rate_limiter
+        .get_or_insert_with(|| RateLimiterConfig::new(0.0, 1))
+}
+
+fn circuit_breaker(builder: &mut ClientConfigBuilder) -> &mut CircuitBreakerConfig {
+    builder
+        .circuit_breaker
+        .get_or_insert_with(CircuitBreakerConfig::default)
+}
+
+fn cache(builder: &mut ClientConfigBuilder) -> &mut CacheConfig {
+    builder.cache.get_or_insert_with(CacheConfig::default)
+}
+
+fn disk_cache(builder: &mut ClientConfigBuilder) -> &mut DiskCacheConfig {
+    builder
+        .disk_cache
+        .get_or_insert_with(|| DiskCacheConfig::new(""))
+}
+
+fn cassette(builder: &mut ClientConfigBuilder) -> &mut CassetteConfig {
+    // Replay unless configured otherwise, so a partial section never sends requests.
+    builder
+        .cassette
+        .get_or_insert_with(|| CassetteConfig::replay(""))
+}
+
+fn set_ttl(
+    builder: &mut ClientConfigBuilder,
+    resource: CacheResource,
+    value: &str,
+    source: &str,
+) -> Result<()> {
+    let ttl = parse_duration(value, source)?;
+    cache(builder).ttls.insert(resource, ttl);
+    Ok(())
+}
+
+fn invalid(source: &str, value: &str, expected: &str) -> Error {
+    Error::Config(format!(
+        "invalid value for {}: {:?} ({})",
+        source, value, expected
+    ))
+}
+
+fn parse<T: std::str::FromStr>(value: &str, source: &str) -> Result<T> {
+    value.parse().map_err(|_| {
+        let expected = match std::any::type_name::<T>() {
+            "f64" => "expected a number",
+            _ => "expected a non-negative integer",
+        };
+        invalid(source, value, expected)
+    })
+}
+
+fn parse_bool(value: &str, source: &str) -> Result<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Ok(true),
+        "false" | "0" | "no" | "off" => Ok(false),
+        _ => Err(invalid(source, value, "expected true or false")),
+    }
+}
+
+/// Parse a duration such as `30s`, `500ms`, `5m` or `1h`.
+///
+/// A plain number is taken as seconds.
+pub(crate) fn parse_duration(value: &str, source: &str) -> Result<Duration> {
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+
+    let scale = match unit.trim() {
+        "ms" => 0.001,
+        "" | "s" => 1.0,
+        "m" => 60.0,
+        "h" => 3600.0,
+        _ => f64::NAN,
+    };
+    number
+        .parse::<f64>()
+        .ok()
+        .and_then(|number| Duration::try_from_secs_f64(number * scale).ok())
+        .ok_or_else(|| {
+            invalid(
+                source,
+                value,
+                "expected a duration such as \"30s\" or \"500ms\"",
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ClientConfig;
+    use std::collections::HashMap;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    fn write_file(dir: &tempfile::TempDir, name: &str, contents: &str) -> std::path::PathBuf {
+        let path = dir.path().join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        let parse = |value| parse_duration(value, "TEST");
+        assert_eq!(parse("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse("500ms").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse("1.5").unwrap(), Duration::from_millis(1500));
+        assert_eq!(parse("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse("1h").unwrap(), Duration::from_secs(3600));
+        assert_eq!(
+            parse("soon").unwrap_err().to_string(),
+            "configuration error: invalid value for TEST: \"soon\" \
+             (expected a duration such as \"30s\" or \"500ms\")"
+        );
+        assert!(parse("5d").is_err());
+        assert!(parse("").is_err());
+        // Finite but too large for a `Duration`.
+        assert!(matches!(
+            parse("100000000000000000000"),
+            Err(Error::Config(_))
+        ));
+        assert!(matches!(parse("1e30h"), Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn test_env_name() {
+        assert_eq!(env_name("api_key"), "EARNINGSFEED_API_KEY");
+        assert_eq!(
+            env_name("cache.ttl.companies"),
+            "EARNINGSFEED_CACHE_TTL_COMPANIES"
+        );
+    }
+
+    #[test]
+    fn test_every_setting_is_handled() {
+        let dir = tempfile::tempdir().unwrap();
+        let certificates = write_file(&dir, "roots.pem", "");
+
+        for key in SETTINGS {
+            let value = match *key {
+                "transport.proxy" => "http://proxy.internal:3128",
+                "transport.root_certificates" => certificates.to_str().unwrap(),
+                "timeout"
+                | "retry.base_delay"
+                | "retry.max_delay"
+                | "retry.max_rate_limit_wait"
+                | "cache.provisional_ttl" => "1s",
+                k if k.starts_with("cache.ttl.") => "1s",
+                k if k.ends_with("enabled") || k.ends_with("adaptive") => "true",
+                "retry.transport_errors" | "cache.final_filings" | "cache.lists" => "true",
+                "disk_cache.offline" => "true",
+                "single_flight" | "metrics" => "true",
+                "retry.statuses" => "503",
+                "cassette.mode" => "replay",
+                "retry.jitter" => "0.5",
+                _ => "1",
+            };
+            let result = apply(ClientConfig::builder(), key, value, key);
+            assert!(result.is_ok(), "{}: {:?}", key, result.err());
+        }
+    }
+
+    #[test]
+    fn test_settings_cover_config_fields() {
+        // Adding a field to `ClientConfig` breaks this until it is added
+        // to `SETTINGS` and `apply` (or listed here as not loadable).
+        let ClientConfig {
+            api_key: _,
+            credentials: _, // only `FileCredentials` via `api_key_file`
+            base_url: _,
+            timeout: _,
+            retry: _,
+            rate_limiter: _,
+            circuit_breaker: _, // `on_state_change` is not expressible as text
+            middleware: _,      // not expressible as text
+            cache: _,
+            disk_cache: _,
+            cassette: _,
+            single_flight: _,
+            metrics: _,
+            transport: _, // `http_client` is not expressible as text
+        } = ClientConfig::builder().api_key("key").build().unwrap();
+    }
+
+    #[test]
+    fn test_from_env() {
+        let vars = env(&[
+            ("EARNINGSFEED_API_KEY", "env_key"),
+            ("EARNINGSFEED_BASE_URL", "http://localhost:8080"),
+            ("EARNINGSFEED_TIMEOUT", "45s"),
+            ("EARNINGSFEED_RETRY_MAX_ATTEMPTS", "5"),
+            ("EARNINGSFEED_RETRY_STATUSES", "429, 503"),
+            ("EARNINGSFEED_RATE_LIMITER_REQUESTS_PER_SECOND", "10"),
+            ("EARNINGSFEED_RATE_LIMITER_BURST", "20"),
+            ("EARNINGSFEED_CACHE_TTL_COMPANIES", "1h"),
+            ("EARNINGSFEED_SINGLE_FLIGHT", "false"),
+        ]);
+        let config = apply_env(ClientConfig::builder(), vars)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(config.api_key.expose_secret(), "env_key");
+        assert_eq!(config.base_url.as_deref(), Some("http://localhost:8080"));
+        assert_eq!(config.timeout, Some(Duration::from_secs(45)));
+        let retry = config.retry.unwrap();
+        assert_eq!(retry.max_attempts, 5);
+        assert_eq!(retry.retry_statuses, vec![429, 503]);
+        assert_eq!(config.rate_limiter, Some(RateLimiterConfig::new(10.0, 20)));
+        assert_eq!(
+            config.cache.unwrap().ttls[&CacheResource::Companies],
+            Duration::from_secs(3600)
+        );
+        assert!(!config.single_flight);
+    }
+
+    #[test]
+    fn test_from_env_reports_malformed_values() {
+        let vars = env(&[
+            ("EARNINGSFEED_API_KEY", "env_key"),
+            ("EARNINGSFEED_RETRY_MAX_ATTEMPTS", "many"),
+        ]);
+        let err = apply_env(ClientConfig::builder(), vars).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "configuration error: invalid value for EARNINGSFEED_RETRY_MAX_ATTEMPTS: \
+             \"many\" (expected a non-negative integer)"
+        );
+
+        let vars = env(&[("EARNINGSFEED_TRANSPORT_ROOT_CERTIFICATES", "/missing.pem")]);
+        let err = apply_env(ClientConfig::builder(), vars).unwrap_err();
+        assert!(err.to_string().starts_with(
+            "configuration error: invalid value for \
+             EARNINGSFEED_TRANSPORT_ROOT_CERTIFICATES: \"/missing.pem\" (expected PEM files:"
+        ));
+
+        let vars = env(&[("EARNINGSFEED_RETRY_JITTER", "2")]);
+        assert!(apply_env(ClientConfig::builder(), vars).is_err());
+
+        let vars = env(&[("EARNINGSFEED_DISK_CACHE_OFFLINE", "true")]);
+        let err = apply_env(ClientConfig::builder(), vars).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "configuration error: disk_cache.dir is required to enable the disk cache"
+        );
+    }
+
+    #[test]
+    fn test_json_profiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(
+            &dir,
+            "earningsfeed.json",
+            r#"{
+                "default_profile": "prod",
+                "profiles": {
+                    "prod": {
+                        "api_key": "prod_key",
+                        "retry": { "max_attempts": 5, "base_delay": "250ms" },
+                        "disk_cache": { "dir": "/var/cache/earningsfeed", "offline": false }
+                    },
+                    "local": {
+                        "api_key": "test",
+                        "base_url": "http://localhost:8080",
+                        "retry": { "enabled": false, "max_attempts": 2 },
+                        "cache": { "enabled": true }
+                    }
+                }
+            }"#,
+        );
+
+        let prod = apply_file(ClientConfig::builder(), &path, None)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(prod.api_key.expose_secret(), "prod_key");
+        let retry = prod.retry.unwrap();
+        assert_eq!(retry.max_attempts, 5);
+        assert_eq!(retry.base_delay, Duration::from_millis(250));
+        assert_eq!(
+            prod.disk_cache.unwrap().dir,
+            std::path::PathBuf::from("/var/cache/earningsfeed")
+        );
+
+        let local = apply_file(ClientConfig::builder(), &path, Some("local"))
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(local.base_url.as_deref(), Some("http://localhost:8080"));
+        assert!(local.retry.is_none());
+        assert_eq!(local.cache, Some(CacheConfig::default()));
+
+        let err = apply_file(ClientConfig::builder(), &path, Some("staging")).unwrap_err();
+        assert!(err.to_string().contains("profile 'staging' not found"));
+        assert!(err.to_string().contains("available: local, prod"));
+    }
+
+    #[test]
+    fn test_file_reports_unknown_and_malformed_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(
+            &dir,
+            "config.json",
+            r#"{ "profiles": { "prod": { "retry": { "max_attempt": 5 } } } }"#,
+        );
+        let err = apply_file(ClientConfig::builder(), &path, Some("prod")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "configuration error: unknown setting retry.max_attempt in profile 'prod'"
+        );
+
+        let path = write_file(
+            &dir,
+            "config.json",
+            r#"{ "profiles": { "prod": { "timeout": "fast" } } }"#,
+        );
+        let err = apply_file(ClientConfig::builder(), &path, Some("prod")).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("invalid value for timeout in profile 'prod': \"fast\""));
+
+        let err = apply_file(ClientConfig::builder(), &path, None).unwrap_err();
+        assert!(err.to_string().contains("no profile selected"));
+    }
+
+    #[test]
+    fn test_env_overrides_config_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(
+            &dir,
+            "config.json",
+            r#"{ "profiles": { "staging": {
+                "api_key": "staging_key",
+                "base_url": "https://staging.earningsfeed.com"
+            } } }"#,
+        );
+        let vars = env(&[
+            (ENV_CONFIG_FILE, path.to_str().unwrap()),
+            (ENV_PROFILE, "staging"),
+            ("EARNINGSFEED_API_KEY", "override_key"),
+        ]);
+        let config = apply_env(ClientConfig::builder(), vars)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(config.api_key.expose_secret(), "override_key");
+        assert_eq!(
+            config.base_url.as_deref(),
+            Some("https://staging.earningsfeed.com")
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml_profiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(
+            &dir,
+            "earningsfeed.toml",
+            r#"
+            [profiles.prod]
+            api_key = "prod_key"
+            timeout = "1m"
+
+            [profiles.prod.rate_limiter]
+            requests_per_second = 5.0
+            burst = 10
+
+            [profiles.prod.retry]
+            statuses = [429, 503]
+            "#,
+        );
+        let config = apply_file(ClientConfig::builder(), &path, Some("prod"))
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(config.timeout, Some(Duration::from_secs(60)));
+        assert_eq!(config.rate_limiter, Some(RateLimiterConfig::new(5.0, 10)));
+        assert_eq!(config.retry.unwrap().retry_statuses, vec![429, 503]);
+    }
+
+    #[cfg(not(feature = "toml"))]
+    #[test]
+    fn test_toml_requires_feature() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(&dir, "earningsfeed.toml", "");
+        let err = apply_file(ClientConfig::builder(), &path, Some("prod")).unwrap_err();
+        assert!(err.to_string().contains("require the `toml` feature"));
+    }
+}
@@ -0,0 +1,364 @@
+//! Client-side rate limiting.
+//!
+//! This module provides a token-bucket rate limiter that is shared by all
+//! clones of an [`EarningsFeed`](crate::EarningsFeed) client.
+
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::header::HeaderMap;
+use tokio::time::Instant;
+
+use crate::error::{Error, Result};
+use crate::response::RateLimitInfo;
+
+/// Longest wait for a rate-limit window to reset.
+///
+/// Bounds the waits derived from server-supplied `X-RateLimit-Reset`
+/// headers.
+const MAX_RESET_WAIT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Configuration for the client-side rate limiter.
+///
+/// The limiter is a token bucket: up to `burst` requests may be sent at
+/// once, after which requests are spaced to `requests_per_second`.
+///
+/// # Example
+///
+/// ```rust
+/// use earningsfeed::{ClientConfig, RateLimiterConfig};
+///
+/// let config = ClientConfig::builder()
+///     .api_key("your_api_key")
+///     .rate_limiter(RateLimiterConfig::new(10.0, 20))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimiterConfig {
+    /// Sustained request rate.
+    pub requests_per_second: f64,
+    /// Maximum number of requests that may be sent back-to-back.
+    pub burst: u32,
+    /// Whether to adapt to `X-RateLimit-*` response headers.
+    pub adaptive: bool,
+}
+
+impl RateLimiterConfig {
+    /// Create a new rate limiter configuration.
+    ///
+    /// Adaptation to `X-RateLimit-*` response headers is enabled by default.
+    #[must_use]
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        Self {
+            requests_per_second,
+            burst,
+            adaptive: true,
+        }
+    }
+
+    /// Whether to adapt to `X-RateLimit-Limit`, `X-RateLimit-Remaining`
+    /// and `X-RateLimit-Reset` response headers.
+    #[must_use]
+    pub fn adaptive(mut self, adaptive: bool) -> Self {
+        self.adaptive = adaptive;
+        self
+    }
+
+    /// Check that the rate is positive and the burst at least 1.
+    pub(crate) fn validate(&self) -> Result<()> {
+        if !(self.requests_per_second > 0.0 && self.requests_per_second.is_finite()) {
+            return Err(Error::Config(
+                "rate limiter requests per second must be positive".into(),
+            ));
+        }
+        if self.burst == 0 {
+            return Err(Error::Config(
+                "rate limiter burst must be at least 1".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Token-bucket rate limiter.
+pub(crate) struct RateLimiter {
+    config: RateLimiterConfig,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    tokens: f64,
+    capacity: f64,
+    rate: f64,
+    last_refill: Instant,
+    blocked_until: Option<Instant>,
+}
+
+impl BucketState {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last_refill = now;
+    }
+}
+
+impl RateLimiter {
+    /// Create a new rate limiter with a full bucket.
+    pub(crate) fn new(config: RateLimiterConfig) -> Self {
+        let capacity = f64::from(config.burst);
+        Self {
+            config,
+            state: Mutex::new(BucketState {
+                tokens: capacity,
+                capacity,
+                rate: config.requests_per_second,
+                last_refill: Instant::now(),
+                blocked_until: None,
+            }),
+        }
+    }
+
+    /// Wait until a request may be sent, then consume a token.
+    pub(crate) async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+                let now = Instant::now();
+
+                match state.blocked_until {
+                    Some(until) if until > now => until - now,
+                    _ => {
+                        state.blocked_until = None;
+                        state.refill(now);
+                        if state.tokens >= 1.0 {
+                            state.tokens -= 1.0;
+                            return;
+                        }
+                        Duration::try_from_secs_f64((1.0 - state.tokens) / state.rate)
+                            .map_or(MAX_RESET_WAIT, |wait| wait.min(MAX_RESET_WAIT))
+                    }
+                }
+            };
+
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Adapt the bucket to the rate-limit state reported by the server.
+    pub(crate) fn observe(&self, info: &RateLimitInfo) {
+        if !self.config.adaptive {
+            return;
+        }
+
+        let RateLimitInfo {
+            limit,
+            remaining,
+            reset_at,
+        } = *info;
+        let reset_in = reset_at.map(|reset_at| {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            Duration::from_secs(reset_at.saturating_sub(now)).min(MAX_RESET_WAIT)
+        });
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        state.refill(now);
+
+        if let Some(limit) = limit {
+            state.capacity = f64::from(self.config.burst).min(limit.max(1) as f64);
+            state.tokens = state.tokens.min(state.capacity);
+        }
+
+        let Some(remaining) = remaining else {
+            return;
+        };
+
+        state.tokens = state.tokens.min(remaining as f64);
+        state.rate = self.config.requests_per_second;
+
+        match reset_in {
+            Some(reset_in) if remaining == 0 && !reset_in.is_zero() => {
+                state.blocked_until = now.checked_add(reset_in);
+            }
+            Some(reset_in) if !reset_in.is_zero() => {
+                // Spread the remaining quota over the rest of the window.
+                let server_rate = remaining as f64 / reset_in.as_secs_f64();
+                state.rate = state.rate.min(server_rate);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Parse a response header value.
+pub(crate) fn header_value<T: std::str::FromStr>(headers: &HeaderMap, name: &str) -> Option<T> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn unix_now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    #[test]
+    fn test_client_rejects_invalid_config() {
+        let mut config = crate::ClientConfig::builder()
+            .api_key("test_key")
+            .build()
+            .unwrap();
+        config.rate_limiter = Some(RateLimiterConfig::new(0.0, 1));
+        assert!(matches!(
+            crate::EarningsFeed::with_config(config.clone()),
+            Err(Error::Config(_))
+        ));
+
+        config.rate_limiter = Some(RateLimiterConfig::new(1.0, 0));
+        assert!(crate::EarningsFeed::with_config(config).is_err());
+    }
+
+    #[test]
+    fn test_config_defaults_to_adaptive() {
+        let config = RateLimiterConfig::new(5.0, 10);
+        assert_eq!(config.requests_per_second, 5.0);
+        assert_eq!(config.burst, 10);
+        assert!(config.adaptive);
+        assert!(!config.adaptive(false).adaptive);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_burst_is_immediate() {
+        let limiter = RateLimiter::new(RateLimiterConfig::new(1.0, 5));
+        let start = Instant::now();
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_waits_for_refill_after_burst() {
+        let limiter = RateLimiter::new(RateLimiterConfig::new(2.0, 1));
+        let start = Instant::now();
+        limiter.acquire().await;
+        limiter.acquire().await;
+        limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(1000));
+        assert!(start.elapsed() < Duration::from_millis(1100));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_exhausted_quota_blocks_until_reset() {
+        let limiter = RateLimiter::new(RateLimiterConfig::new(100.0, 10));
+        limiter.observe(&RateLimitInfo {
+            limit: None,
+            remaining: Some(0),
+            reset_at: Some(unix_now() + 3),
+        });
+
+        let start = Instant::now();
+        limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_secs(2));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_far_future_reset_is_capped() {
+        let limiter = RateLimiter::new(RateLimiterConfig::new(100.0, 1));
+        limiter.observe(&RateLimitInfo {
+            limit: None,
+            remaining: Some(0),
+            reset_at: Some(u64::MAX),
+        });
+
+        let start = Instant::now();
+        limiter.acquire().await;
+        assert_eq!(start.elapsed(), MAX_RESET_WAIT);
+
+        // One request left in a huge window slows the rate without
+        // overflowing the wait.
+        limiter.observe(&RateLimitInfo {
+            limit: None,
+            remaining: Some(1),
+            reset_at: Some(u64::MAX),
+        });
+        for _ in 0..2 {
+            let start = Instant::now();
+            limiter.acquire().await;
+            assert!(start.elapsed() <= MAX_RESET_WAIT);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_remaining_caps_available_tokens() {
+        let limiter = RateLimiter::new(RateLimiterConfig::new(1.0, 10));
+        limiter.observe(&RateLimitInfo {
+            remaining: Some(2),
+            ..Default::default()
+        });
+
+        let start = Instant::now();
+        limiter.acquire().await;
+        limiter.acquire().await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(900));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_limit_caps_burst() {
+        let limiter = RateLimiter::new(RateLimiterConfig::new(1.0, 10));
+        limiter.observe(&RateLimitInfo {
+            limit: Some(1),
+            ..Default::default()
+        });
+
+        let start = Instant::now();
+        limiter.acquire().await;
+        limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(900));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_non_adaptive_ignores_headers() {
+        let limiter = RateLimiter::new(RateLimiterConfig::new(1.0, 3).adaptive(false));
+        limiter.observe(&RateLimitInfo {
+            remaining: Some(0),
+            ..Default::default()
+        });
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_header_value_parsing() {
+        let mut map = HeaderMap::new();
+        map.insert(
+            "X-RateLimit-Reset",
+            HeaderValue::from_static(" 1703520000 "),
+        );
+        assert_eq!(
+            header_value::<u64>(&map, "X-RateLimit-Reset"),
+            Some(1703520000)
+        );
+        assert_eq!(header_value::<u64>(&map, "X-RateLimit-Limit"), None);
+    }
+}
@@ -3,32 +3,58 @@
 //! This module provides methods for searching and retrieving
 //! company profiles.
 
-use async_stream::try_stream;
 use futures::Stream;
 
 use crate::client::EarningsFeed;
 use crate::error::Result;
 use crate::models::{Company, CompanySearchResult, PaginatedResponse, SearchCompaniesParams};
+use crate::options::RequestOptions;
+use crate::pagination::Paginator;
+use crate::response::Response;
 
 /// Resource for accessing company data.
 ///
 /// Obtain an instance via [`EarningsFeed::companies()`].
 pub struct CompaniesResource<'a> {
     client: &'a EarningsFeed,
+    options: RequestOptions,
 }
 
 impl<'a> CompaniesResource<'a> {
     /// Create a new companies resource.
     pub(crate) fn new(client: &'a EarningsFeed) -> Self {
-        Self { client }
+        Self {
+            client,
+            options: RequestOptions::default(),
+        }
+    }
+
+    /// Apply per-request options to every call of this resource.
+    ///
+    /// The options also apply to every page fetched by its streams, and
+    /// cancelling their token ends the streams with [`Error::Cancelled`](crate::Error::Cancelled).
+    #[must_use]
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
     }
 
     /// Get a company by CIK.
     ///
     /// Returns the full company profile.
     pub async fn get(&self, cik: u64) -> Result<Company> {
+        self.get_with_meta(cik).await.map(Response::into_inner)
+    }
+
+    /// Get a company by CIK, returning response metadata alongside the data.
+    ///
+    /// Same as [`get`](Self::get), but also returns the request ID,
+    /// latency and rate-limit state of the response.
+    pub async fn get_with_meta(&self, cik: u64) -> Result<Response<Company>> {
         let path = format!("/api/v1/companies/{}", cik);
-        self.client.get::<Company, ()>(&path, None).await
+        self.client
+            .get_with_options::<Company, ()>(&path, None, &self.options)
+            .await
     }
 
     /// Search for companies.
@@ -38,11 +64,38 @@ impl<'a> CompaniesResource<'a> {
         &self,
         params: &SearchCompaniesParams,
     ) -> Result<PaginatedResponse<CompanySearchResult>> {
+        self.search_with_meta(params).await.map(Response::into_inner)
+    }
+
+    /// Search for companies, returning response metadata alongside the data.
+    ///
+    /// Same as [`search`](Self::search), but also returns the request ID,
+    /// latency and rate-limit state of the response.
+    pub async fn search_with_meta(
+        &self,
+        params: &SearchCompaniesParams,
+    ) -> Result<Response<PaginatedResponse<CompanySearchResult>>> {
         self.client
-            .get("/api/v1/companies/search", Some(params))
+            .get_with_options("/api/v1/companies/search", Some(params), &self.options)
             .await
     }
 
+    /// Paginate over all company search results matching the given parameters.
+    ///
+    /// Returns a [`Paginator`] streaming pages or items, which can also limit
+    /// the number of pages or items fetched.
+    pub fn paginate_search(
+        &self,
+        params: SearchCompaniesParams,
+    ) -> Paginator<'a, CompanySearchResult> {
+        Paginator::new(
+            self.client,
+            "/api/v1/companies/search",
+            params,
+            self.options.clone(),
+        )
+    }
+
     /// Iterate over all companies matching the search parameters.
     ///
     /// Returns an async stream that automatically handles pagination.
@@ -50,28 +103,7 @@ impl<'a> CompaniesResource<'a> {
         &self,
         params: SearchCompaniesParams,
     ) -> impl Stream<Item = Result<CompanySearchResult>> + '_ {
-        try_stream! {
-            let mut current_params = params;
-
-            loop {
-                let response = self.search(&current_params).await?;
-
-                for item in response.items {
-                    yield item;
-                }
-
-                if !response.has_more {
-                    break;
-                }
-
-                match response.next_cursor {
-                    Some(cursor) => {
-                        current_params.cursor = Some(cursor);
-                    }
-                    None => break,
-                }
-            }
-        }
+        self.paginate_search(params).items()
     }
 }
 
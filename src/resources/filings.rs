@@ -3,12 +3,15 @@
 //! This module provides methods for listing, retrieving, and iterating
 //! over SEC filings.
 
-use async_stream::try_stream;
 use futures::Stream;
 
+use crate::bulk::{self, BulkFetch};
 use crate::client::EarningsFeed;
 use crate::error::Result;
 use crate::models::{Filing, FilingDetail, ListFilingsParams, PaginatedResponse};
+use crate::options::RequestOptions;
+use crate::pagination::Paginator;
+use crate::response::Response;
 
 /// Resource for accessing SEC filings.
 ///
@@ -33,12 +36,26 @@ use crate::models::{Filing, FilingDetail, ListFilingsParams, PaginatedResponse};
 /// ```
 pub struct FilingsResource<'a> {
     client: &'a EarningsFeed,
+    options: RequestOptions,
 }
 
 impl<'a> FilingsResource<'a> {
     /// Create a new filings resource.
     pub(crate) fn new(client: &'a EarningsFeed) -> Self {
-        Self { client }
+        Self {
+            client,
+            options: RequestOptions::default(),
+        }
+    }
+
+    /// Apply per-request options to every call of this resource.
+    ///
+    /// The options also apply to every page fetched by its streams, and
+    /// cancelling their token ends the streams with [`Error::Cancelled`](crate::Error::Cancelled).
+    #[must_use]
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
     }
 
     /// List filings with optional filters.
@@ -64,7 +81,20 @@ impl<'a> FilingsResource<'a> {
     /// }
     /// ```
     pub async fn list(&self, params: &ListFilingsParams) -> Result<PaginatedResponse<Filing>> {
-        self.client.get("/api/v1/filings", Some(params)).await
+        self.list_with_meta(params).await.map(Response::into_inner)
+    }
+
+    /// List filings, returning response metadata alongside the data.
+    ///
+    /// Same as [`list`](Self::list), but also returns the request ID,
+    /// latency and rate-limit state of the response.
+    pub async fn list_with_meta(
+        &self,
+        params: &ListFilingsParams,
+    ) -> Result<Response<PaginatedResponse<Filing>>> {
+        self.client
+            .get_with_options("/api/v1/filings", Some(params), &self.options)
+            .await
     }
 
     /// Get a specific filing by accession number.
@@ -83,8 +113,44 @@ impl<'a> FilingsResource<'a> {
     /// println!("Documents: {:?}", filing.documents.len());
     /// ```
     pub async fn get(&self, accession_number: &str) -> Result<FilingDetail> {
+        self.get_with_meta(accession_number)
+            .await
+            .map(Response::into_inner)
+    }
+
+    /// Get a specific filing, returning response metadata alongside the data.
+    ///
+    /// Same as [`get`](Self::get), but also returns the request ID,
+    /// latency and rate-limit state of the response.
+    pub async fn get_with_meta(&self, accession_number: &str) -> Result<Response<FilingDetail>> {
         let path = format!("/api/v1/filings/{}", accession_number);
-        self.client.get::<FilingDetail, ()>(&path, None).await
+        self.client
+            .get_with_options::<FilingDetail, ()>(&path, None, &self.options)
+            .await
+    }
+
+    /// Paginate over all filings matching the given parameters.
+    ///
+    /// Returns a [`Paginator`] streaming pages or items, which can also limit
+    /// the number of pages or items fetched.
+    pub fn paginate(&self, params: ListFilingsParams) -> Paginator<'a, Filing> {
+        Paginator::new(self.client, "/api/v1/filings", params, self.options.clone())
+    }
+
+    /// Fetch all filings in the date range of the given parameters.
+    ///
+    /// Returns a [`BulkFetch`] splitting `start_date`..=`end_date` into
+    /// windows that are paginated concurrently, and merging the results
+    /// newest first without duplicates.
+    pub fn bulk(&self, params: ListFilingsParams) -> BulkFetch<'a, Filing> {
+        BulkFetch::new(
+            self.client,
+            "/api/v1/filings",
+            params,
+            self.options.clone(),
+            bulk::filing_sort_key,
+            bulk::filing_key,
+        )
     }
 
     /// Iterate over all filings matching the given parameters.
@@ -111,28 +177,7 @@ impl<'a> FilingsResource<'a> {
     /// }
     /// ```
     pub fn iter(&self, params: ListFilingsParams) -> impl Stream<Item = Result<Filing>> + '_ {
-        try_stream! {
-            let mut current_params = params;
-
-            loop {
-                let response = self.list(&current_params).await?;
-
-                for item in response.items {
-                    yield item;
-                }
-
-                if !response.has_more {
-                    break;
-                }
-
-                match response.next_cursor {
-                    Some(cursor) => {
-                        current_params.cursor = Some(cursor);
-                    }
-                    None => break,
-                }
-            }
-        }
+        self.paginate(params).items()
     }
 }
 
@@ -142,6 +187,7 @@ mod tests {
     use crate::models::FilingStatus;
     use futures::StreamExt;
     use std::pin::pin;
+    use tokio_util::sync::CancellationToken;
     use wiremock::matchers::{method, path, query_param};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -266,6 +312,34 @@ mod tests {
         assert!(response.items.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_list_filings_with_meta() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("X-Request-Id", "req_123")
+                    .insert_header("X-RateLimit-Remaining", "42")
+                    .set_body_json(serde_json::json!({
+                        "items": [],
+                        "nextCursor": null,
+                        "hasMore": false
+                    })),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let client = setup_client(&mock_server).await;
+        let params = ListFilingsParams::default();
+        let response = client.filings().list_with_meta(&params).await.unwrap();
+
+        assert!(response.data.items.is_empty());
+        assert_eq!(response.meta.request_id, Some("req_123".to_string()));
+        assert_eq!(response.meta.rate_limit.unwrap().remaining, Some(42));
+    }
+
     #[tokio::test]
     async fn test_get_filing() {
         let mock_server = MockServer::start().await;
@@ -439,4 +513,51 @@ mod tests {
         assert_eq!(filings[0].as_ref().unwrap().form_type, "10-K");
         assert_eq!(filings[1].as_ref().unwrap().form_type, "10-Q");
     }
+
+    #[tokio::test]
+    async fn test_iter_with_options_stops_when_cancelled() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .and(wiremock::matchers::header("X-Correlation-Id", "backfill"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [
+                    {
+                        "accessionNumber": "0000950170-24-000001",
+                        "cik": 320193,
+                        "formType": "10-K",
+                        "filedAt": "2024-01-15T16:30:00Z",
+                        "provisional": false,
+                        "sizeBytes": 12345,
+                        "url": "https://www.sec.gov/...",
+                        "title": "Form 10-K Page 1",
+                        "status": "final",
+                        "updatedAt": "2024-01-15T17:00:00Z",
+                        "sortedAt": "2024-01-15T16:30:00Z"
+                    }
+                ],
+                "nextCursor": "cursor_page_2",
+                "hasMore": true
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = setup_client(&mock_server).await;
+        let token = CancellationToken::new();
+        let options = RequestOptions::new()
+            .correlation_id("backfill")
+            .cancellation_token(token.clone());
+        let filings_resource = client.filings().with_options(options);
+        let mut stream = pin!(filings_resource.iter(ListFilingsParams::default()));
+
+        assert!(stream.next().await.unwrap().is_ok());
+        token.cancel();
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(crate::error::Error::Cancelled))
+        ));
+        assert!(stream.next().await.is_none());
+    }
 }
@@ -3,24 +3,41 @@
 //! This module provides methods for listing and iterating
 //! over Form 3/4/5 insider trading data.
 
-use async_stream::try_stream;
 use futures::Stream;
 
+use crate::bulk::{self, BulkFetch};
 use crate::client::EarningsFeed;
 use crate::error::Result;
 use crate::models::{InsiderTransaction, ListInsiderParams, PaginatedResponse};
+use crate::options::RequestOptions;
+use crate::pagination::Paginator;
+use crate::response::Response;
 
 /// Resource for accessing insider transactions.
 ///
 /// Obtain an instance via [`EarningsFeed::insider()`].
 pub struct InsiderResource<'a> {
     client: &'a EarningsFeed,
+    options: RequestOptions,
 }
 
 impl<'a> InsiderResource<'a> {
     /// Create a new insider resource.
     pub(crate) fn new(client: &'a EarningsFeed) -> Self {
-        Self { client }
+        Self {
+            client,
+            options: RequestOptions::default(),
+        }
+    }
+
+    /// Apply per-request options to every call of this resource.
+    ///
+    /// The options also apply to every page fetched by its streams, and
+    /// cancelling their token ends the streams with [`Error::Cancelled`](crate::Error::Cancelled).
+    #[must_use]
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
     }
 
     /// List insider transactions with optional filters.
@@ -30,11 +47,51 @@ impl<'a> InsiderResource<'a> {
         &self,
         params: &ListInsiderParams,
     ) -> Result<PaginatedResponse<InsiderTransaction>> {
+        self.list_with_meta(params).await.map(Response::into_inner)
+    }
+
+    /// List insider transactions, returning response metadata alongside the data.
+    ///
+    /// Same as [`list`](Self::list), but also returns the request ID,
+    /// latency and rate-limit state of the response.
+    pub async fn list_with_meta(
+        &self,
+        params: &ListInsiderParams,
+    ) -> Result<Response<PaginatedResponse<InsiderTransaction>>> {
         self.client
-            .get("/api/v1/insider/transactions", Some(params))
+            .get_with_options("/api/v1/insider/transactions", Some(params), &self.options)
             .await
     }
 
+    /// Paginate over all insider transactions matching the given parameters.
+    ///
+    /// Returns a [`Paginator`] streaming pages or items, which can also limit
+    /// the number of pages or items fetched.
+    pub fn paginate(&self, params: ListInsiderParams) -> Paginator<'a, InsiderTransaction> {
+        Paginator::new(
+            self.client,
+            "/api/v1/insider/transactions",
+            params,
+            self.options.clone(),
+        )
+    }
+
+    /// Fetch all insider transactions in the date range of the given parameters.
+    ///
+    /// Returns a [`BulkFetch`] splitting `start_date`..=`end_date` into
+    /// windows that are paginated concurrently, and merging the results
+    /// newest first without duplicates.
+    pub fn bulk(&self, params: ListInsiderParams) -> BulkFetch<'a, InsiderTransaction> {
+        BulkFetch::new(
+            self.client,
+            "/api/v1/insider/transactions",
+            params,
+            self.options.clone(),
+            bulk::insider_sort_key,
+            bulk::insider_key,
+        )
+    }
+
     /// Iterate over all insider transactions matching the given parameters.
     ///
     /// Returns an async stream that automatically handles pagination.
@@ -42,28 +99,7 @@ impl<'a> InsiderResource<'a> {
         &self,
         params: ListInsiderParams,
     ) -> impl Stream<Item = Result<InsiderTransaction>> + '_ {
-        try_stream! {
-            let mut current_params = params;
-
-            loop {
-                let response = self.list(&current_params).await?;
-
-                for item in response.items {
-                    yield item;
-                }
-
-                if !response.has_more {
-                    break;
-                }
-
-                match response.next_cursor {
-                    Some(cursor) => {
-                        current_params.cursor = Some(cursor);
-                    }
-                    None => break,
-                }
-            }
-        }
+        self.paginate(params).items()
     }
 }
 
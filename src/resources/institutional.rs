@@ -3,24 +3,40 @@
 //! This module provides methods for listing and iterating
 //! over 13F institutional holdings data.
 
-use async_stream::try_stream;
 use futures::Stream;
 
 use crate::client::EarningsFeed;
 use crate::error::Result;
 use crate::models::{InstitutionalHolding, ListInstitutionalParams, PaginatedResponse};
+use crate::options::RequestOptions;
+use crate::pagination::Paginator;
+use crate::response::Response;
 
 /// Resource for accessing institutional holdings.
 ///
 /// Obtain an instance via [`EarningsFeed::institutional()`].
 pub struct InstitutionalResource<'a> {
     client: &'a EarningsFeed,
+    options: RequestOptions,
 }
 
 impl<'a> InstitutionalResource<'a> {
     /// Create a new institutional resource.
     pub(crate) fn new(client: &'a EarningsFeed) -> Self {
-        Self { client }
+        Self {
+            client,
+            options: RequestOptions::default(),
+        }
+    }
+
+    /// Apply per-request options to every call of this resource.
+    ///
+    /// The options also apply to every page fetched by its streams, and
+    /// cancelling their token ends the streams with [`Error::Cancelled`](crate::Error::Cancelled).
+    #[must_use]
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
     }
 
     /// List institutional holdings with optional filters.
@@ -30,11 +46,39 @@ impl<'a> InstitutionalResource<'a> {
         &self,
         params: &ListInstitutionalParams,
     ) -> Result<PaginatedResponse<InstitutionalHolding>> {
+        self.list_with_meta(params).await.map(Response::into_inner)
+    }
+
+    /// List institutional holdings, returning response metadata alongside the data.
+    ///
+    /// Same as [`list`](Self::list), but also returns the request ID,
+    /// latency and rate-limit state of the response.
+    pub async fn list_with_meta(
+        &self,
+        params: &ListInstitutionalParams,
+    ) -> Result<Response<PaginatedResponse<InstitutionalHolding>>> {
         self.client
-            .get("/api/v1/institutional/holdings", Some(params))
+            .get_with_options(
+                "/api/v1/institutional/holdings",
+                Some(params),
+                &self.options,
+            )
             .await
     }
 
+    /// Paginate over all institutional holdings matching the given parameters.
+    ///
+    /// Returns a [`Paginator`] streaming pages or items, which can also limit
+    /// the number of pages or items fetched.
+    pub fn paginate(&self, params: ListInstitutionalParams) -> Paginator<'a, InstitutionalHolding> {
+        Paginator::new(
+            self.client,
+            "/api/v1/institutional/holdings",
+            params,
+            self.options.clone(),
+        )
+    }
+
     /// Iterate over all institutional holdings matching the given parameters.
     ///
     /// Returns an async stream that automatically handles pagination.
@@ -42,28 +86,7 @@ impl<'a> InstitutionalResource<'a> {
         &self,
         params: ListInstitutionalParams,
     ) -> impl Stream<Item = Result<InstitutionalHolding>> + '_ {
-        try_stream! {
-            let mut current_params = params;
-
-            loop {
-                let response = self.list(&current_params).await?;
-
-                for item in response.items {
-                    yield item;
-                }
-
-                if !response.has_more {
-                    break;
-                }
-
-                match response.next_cursor {
-                    Some(cursor) => {
-                        current_params.cursor = Some(cursor);
-                    }
-                    None => break,
-                }
-            }
-        }
+        self.paginate(params).items()
     }
 }
 
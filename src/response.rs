@@ -0,0 +1,152 @@
+//! Response metadata types.
+//!
+//! This module provides [`Response`], which pairs deserialized API data
+//! with [`ResponseMeta`] extracted from the HTTP response.
+
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+
+use crate::rate_limit::header_value;
+
+/// Name of the header carrying the server-assigned request ID.
+pub(crate) const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Rate-limit state reported by the API.
+///
+/// Parsed from the `X-RateLimit-Limit`, `X-RateLimit-Remaining` and
+/// `X-RateLimit-Reset` response headers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    /// Maximum number of requests in the current window.
+    pub limit: Option<u64>,
+    /// Requests remaining in the current window.
+    pub remaining: Option<u64>,
+    /// Unix timestamp when the current window resets.
+    pub reset_at: Option<u64>,
+}
+
+impl RateLimitInfo {
+    /// Parse rate-limit headers, returning `None` if none are present.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let info = Self {
+            limit: header_value(headers, "X-RateLimit-Limit"),
+            remaining: header_value(headers, "X-RateLimit-Remaining"),
+            reset_at: header_value(headers, "X-RateLimit-Reset"),
+        };
+
+        if info == Self::default() {
+            None
+        } else {
+            Some(info)
+        }
+    }
+}
+
+/// Metadata about an API response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseMeta {
+    /// HTTP status code.
+    pub status: u16,
+    /// Server-assigned request ID, useful when contacting support.
+    pub request_id: Option<String>,
+    /// Time from sending the request to receiving the full body.
+    pub latency: Duration,
+    /// Rate-limit state, if the server reported one.
+    pub rate_limit: Option<RateLimitInfo>,
+    /// Whether the response was served from the client's cache.
+    ///
+    /// For cached responses, the request ID and rate-limit state are those
+    /// of the original response.
+    pub cached: bool,
+}
+
+impl ResponseMeta {
+    /// Build metadata from response headers.
+    pub(crate) fn from_headers(status: u16, headers: &HeaderMap, latency: Duration) -> Self {
+        Self {
+            status,
+            request_id: header_value(headers, REQUEST_ID_HEADER),
+            latency,
+            rate_limit: RateLimitInfo::from_headers(headers),
+            cached: false,
+        }
+    }
+}
+
+/// API data together with response metadata.
+///
+/// Returned by the `*_with_meta` resource methods.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let response = client.filings().list_with_meta(&params).await?;
+/// println!("request id: {:?}", response.meta.request_id);
+/// println!("remaining quota: {:?}", response.meta.rate_limit);
+///
+/// for filing in response.data.items {
+///     println!("{}", filing.title);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Response<T> {
+    /// Deserialized response body.
+    pub data: T,
+    /// Response metadata.
+    pub meta: ResponseMeta,
+}
+
+impl<T> Response<T> {
+    /// Discard the metadata and return the response body.
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_rate_limit_info_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-RateLimit-Limit", HeaderValue::from_static("100"));
+        headers.insert("X-RateLimit-Remaining", HeaderValue::from_static("42"));
+        headers.insert("X-RateLimit-Reset", HeaderValue::from_static("1703520000"));
+
+        let info = RateLimitInfo::from_headers(&headers).unwrap();
+        assert_eq!(info.limit, Some(100));
+        assert_eq!(info.remaining, Some(42));
+        assert_eq!(info.reset_at, Some(1703520000));
+    }
+
+    #[test]
+    fn test_rate_limit_info_absent() {
+        assert!(RateLimitInfo::from_headers(&HeaderMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_response_meta_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(REQUEST_ID_HEADER, HeaderValue::from_static("req_123"));
+
+        let meta = ResponseMeta::from_headers(200, &headers, Duration::from_millis(12));
+        assert_eq!(meta.status, 200);
+        assert_eq!(meta.request_id, Some("req_123".to_string()));
+        assert_eq!(meta.latency, Duration::from_millis(12));
+        assert!(meta.rate_limit.is_none());
+        assert!(!meta.cached);
+    }
+
+    #[test]
+    fn test_response_into_inner() {
+        let response = Response {
+            data: vec![1, 2, 3],
+            meta: ResponseMeta::from_headers(200, &HeaderMap::new(), Duration::ZERO),
+        };
+        assert_eq!(response.into_inner(), vec![1, 2, 3]);
+    }
+}
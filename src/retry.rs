@@ -0,0 +1,451 @@
+//! Retry policy for transient API failures.
+//!
+//! This module provides the [`RetryPolicy`] type and its builder for
+//! configuring automatic retries with exponential backoff.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::Error;
+
+/// Default maximum number of attempts (including the first request).
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Default base delay for exponential backoff.
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Default maximum delay between attempts.
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Default maximum wait for a rate-limit reset.
+pub const DEFAULT_MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(5 * 60);
+
+/// HTTP status codes retried by default.
+pub const DEFAULT_RETRY_STATUSES: [u16; 4] = [429, 502, 503, 504];
+
+/// HTTP status codes that are never retried, regardless of configuration.
+const NEVER_RETRY: [u16; 3] = [400, 401, 404];
+
+/// Callback invoked before each retry.
+pub type RetryHook = Arc<dyn Fn(&RetryEvent<'_>) + Send + Sync>;
+
+/// Information about a retry that is about to happen.
+///
+/// Passed to the hook registered with [`RetryPolicyBuilder::on_retry`].
+#[derive(Debug)]
+pub struct RetryEvent<'a> {
+    /// The attempt that just failed (1-based).
+    pub attempt: u32,
+    /// How long the client will wait before the next attempt.
+    pub delay: Duration,
+    /// The error returned by the failed attempt.
+    pub error: &'a Error,
+}
+
+/// Policy controlling automatic retries of failed requests.
+///
+/// Use [`RetryPolicy::builder()`] to create a new policy.
+///
+/// Rate-limited requests (HTTP 429) wait until the `X-RateLimit-Reset`
+/// timestamp before retrying, unless that is more than
+/// `max_rate_limit_wait` away, in which case the [`Error::RateLimit`] is
+/// returned. Other retryable failures back off exponentially from
+/// `base_delay` up to `max_delay`, with optional jitter. HTTP 400, 401 and
+/// 404 responses are never retried.
+///
+/// # Example
+///
+/// ```rust
+/// use earningsfeed::{ClientConfig, RetryPolicy};
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::builder()
+///     .max_attempts(5)
+///     .base_delay(Duration::from_millis(250))
+///     .max_delay(Duration::from_secs(10))
+///     .build();
+///
+/// let config = ClientConfig::builder()
+///     .api_key("your_api_key")
+///     .retry_policy(policy)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first request.
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled for each further retry.
+    pub base_delay: Duration,
+    /// Upper bound for the backoff delay.
+    pub max_delay: Duration,
+    /// Longest wait for a rate-limit reset before giving up.
+    pub max_rate_limit_wait: Duration,
+    /// Fraction of the backoff delay to randomize (0.0 - 1.0).
+    pub jitter: f64,
+    /// HTTP status codes that trigger a retry.
+    pub retry_statuses: Vec<u16>,
+    /// Whether to retry transport errors (timeouts, connection failures).
+    pub retry_transport_errors: bool,
+    /// Callback invoked before each retry.
+    pub on_retry: Option<RetryHook>,
+}
+
+impl RetryPolicy {
+    /// Create a new retry policy builder.
+    #[must_use]
+    pub fn builder() -> RetryPolicyBuilder {
+        RetryPolicyBuilder::default()
+    }
+
+    /// Whether the given error should be retried under this policy.
+    #[must_use]
+    pub fn should_retry(&self, error: &Error) -> bool {
+        match error {
+            Error::RateLimit { .. } | Error::Api { .. } => {
+                error.status().is_some_and(|status| self.retries_status(status))
+            }
+            _ => self.retry_transport_errors && error.is_transport(),
+        }
+    }
+
+    /// Compute the delay before the next attempt, or `None` if the request
+    /// should not be retried.
+    ///
+    /// `attempt` is the 1-based number of the attempt that just failed.
+    #[must_use]
+    pub fn next_delay(&self, attempt: u32, error: &Error) -> Option<Duration> {
+        if attempt >= self.max_attempts || !self.should_retry(error) {
+            return None;
+        }
+
+        // Rate-limited requests wait as long as the server asks, within
+        // `max_rate_limit_wait`.
+        match error.retry_after() {
+            Some(wait) if wait > self.max_rate_limit_wait => None,
+            Some(wait) => Some(wait),
+            None => Some(self.backoff(attempt)),
+        }
+    }
+
+    /// Exponential backoff delay for the given attempt, with jitter applied.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .saturating_mul(1u32 << exponent)
+            .min(self.max_delay);
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return delay;
+        }
+        delay.mul_f64(1.0 - jitter * fastrand::f64())
+    }
+
+    fn retries_status(&self, status: u16) -> bool {
+        !NEVER_RETRY.contains(&status) && self.retry_statuses.contains(&status)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicyBuilder::default().build()
+    }
+}
+
+impl std::fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("base_delay", &self.base_delay)
+            .field("max_delay", &self.max_delay)
+            .field("max_rate_limit_wait", &self.max_rate_limit_wait)
+            .field("jitter", &self.jitter)
+            .field("retry_statuses", &self.retry_statuses)
+            .field("retry_transport_errors", &self.retry_transport_errors)
+            .field("on_retry", &self.on_retry.is_some())
+            .finish()
+    }
+}
+
+/// Builder for [`RetryPolicy`].
+pub struct RetryPolicyBuilder {
+    policy: RetryPolicy,
+}
+
+impl Default for RetryPolicyBuilder {
+    fn default() -> Self {
+        Self {
+            policy: RetryPolicy {
+                max_attempts: DEFAULT_MAX_ATTEMPTS,
+                base_delay: DEFAULT_BASE_DELAY,
+                max_delay: DEFAULT_MAX_DELAY,
+                max_rate_limit_wait: DEFAULT_MAX_RATE_LIMIT_WAIT,
+                jitter: 0.2,
+                retry_statuses: DEFAULT_RETRY_STATUSES.to_vec(),
+                retry_transport_errors: true,
+                on_retry: None,
+            },
+        }
+    }
+}
+
+impl RetryPolicyBuilder {
+    /// Maximum number of attempts, including the first request.
+    ///
+    /// Defaults to 3. Values below 1 are treated as 1.
+    #[must_use]
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.policy.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Delay before the first retry.
+    ///
+    /// Defaults to 500ms.
+    #[must_use]
+    pub fn base_delay(mut self, delay: Duration) -> Self {
+        self.policy.base_delay = delay;
+        self
+    }
+
+    /// Upper bound for the backoff delay.
+    ///
+    /// Defaults to 30 seconds. Does not apply to rate-limit waits, see
+    /// [`max_rate_limit_wait`](Self::max_rate_limit_wait).
+    #[must_use]
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.policy.max_delay = delay;
+        self
+    }
+
+    /// Longest wait for a rate-limit reset.
+    ///
+    /// Defaults to 5 minutes. If the server asks to wait longer, the
+    /// request fails with [`Error::RateLimit`] instead of being retried.
+    #[must_use]
+    pub fn max_rate_limit_wait(mut self, wait: Duration) -> Self {
+        self.policy.max_rate_limit_wait = wait;
+        self
+    }
+
+    /// Fraction of the backoff delay to randomize (0.0 - 1.0).
+    ///
+    /// Defaults to 0.2. Use 0.0 to disable jitter.
+    #[must_use]
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.policy.jitter = jitter;
+        self
+    }
+
+    /// HTTP status codes that trigger a retry.
+    ///
+    /// Defaults to 429, 502, 503 and 504. 400, 401 and 404 are ignored.
+    #[must_use]
+    pub fn retry_statuses<I>(mut self, statuses: I) -> Self
+    where
+        I: IntoIterator<Item = u16>,
+    {
+        self.policy.retry_statuses = statuses.into_iter().collect();
+        self
+    }
+
+    /// Whether to retry transport errors (timeouts, connection failures).
+    ///
+    /// Defaults to `true`.
+    #[must_use]
+    pub fn retry_transport_errors(mut self, retry: bool) -> Self {
+        self.policy.retry_transport_errors = retry;
+        self
+    }
+
+    /// Register a callback invoked before each retry.
+    #[must_use]
+    pub fn on_retry<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&RetryEvent<'_>) + Send + Sync + 'static,
+    {
+        self.policy.on_retry = Some(Arc::new(hook));
+        self
+    }
+
+    /// Build the retry policy.
+    #[must_use]
+    pub fn build(self) -> RetryPolicy {
+        self.policy
+    }
+}
+
+impl std::fmt::Debug for RetryPolicyBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryPolicyBuilder")
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn api_error(status: u16) -> Error {
+        Error::Api {
+            status,
+            message: "error".to_string(),
+            code: None,
+            request_id: None,
+            context: Box::default(),
+        }
+    }
+
+    fn rate_limit_error(reset_at: Option<u64>) -> Error {
+        Error::RateLimit {
+            reset_at,
+            retry_after: None,
+            request_id: None,
+        }
+    }
+
+    #[test]
+    fn test_default_policy() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.max_attempts, DEFAULT_MAX_ATTEMPTS);
+        assert_eq!(policy.base_delay, DEFAULT_BASE_DELAY);
+        assert_eq!(policy.max_delay, DEFAULT_MAX_DELAY);
+        assert_eq!(policy.max_rate_limit_wait, DEFAULT_MAX_RATE_LIMIT_WAIT);
+        assert_eq!(policy.retry_statuses, vec![429, 502, 503, 504]);
+        assert!(policy.retry_transport_errors);
+        assert!(policy.on_retry.is_none());
+    }
+
+    #[test]
+    fn test_should_retry_server_errors() {
+        let policy = RetryPolicy::default();
+        assert!(policy.should_retry(&api_error(502)));
+        assert!(policy.should_retry(&api_error(503)));
+        assert!(policy.should_retry(&api_error(504)));
+        assert!(!policy.should_retry(&api_error(500)));
+        assert!(policy.should_retry(&rate_limit_error(None)));
+        assert!(policy.should_retry(&Error::Timeout {
+            timeout: Duration::from_secs(30),
+            source: None,
+        }));
+    }
+
+    #[test]
+    fn test_never_retries_client_errors() {
+        let policy = RetryPolicy::builder()
+            .retry_statuses([400, 401, 404, 503])
+            .build();
+        assert!(!policy.should_retry(&api_error(400)));
+        assert!(!policy.should_retry(&api_error(401)));
+        assert!(!policy.should_retry(&api_error(404)));
+        assert!(!policy.should_retry(&Error::Authentication));
+        assert!(!policy.should_retry(&Error::NotFound {
+            path: "/api/v1/filings/x".to_string(),
+            request_id: None,
+        }));
+        assert!(!policy.should_retry(&Error::Validation {
+            message: "bad".to_string(),
+            request_id: None,
+            context: Box::default(),
+        }));
+    }
+
+    #[test]
+    fn test_next_delay_respects_max_attempts() {
+        let policy = RetryPolicy::builder().max_attempts(2).jitter(0.0).build();
+        assert!(policy.next_delay(1, &api_error(503)).is_some());
+        assert!(policy.next_delay(2, &api_error(503)).is_none());
+    }
+
+    #[test]
+    fn test_exponential_backoff_is_capped() {
+        let policy = RetryPolicy::builder()
+            .max_attempts(10)
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_millis(350))
+            .jitter(0.0)
+            .build();
+        let err = api_error(503);
+        assert_eq!(policy.next_delay(1, &err), Some(Duration::from_millis(100)));
+        assert_eq!(policy.next_delay(2, &err), Some(Duration::from_millis(200)));
+        assert_eq!(policy.next_delay(3, &err), Some(Duration::from_millis(350)));
+        assert_eq!(policy.next_delay(9, &err), Some(Duration::from_millis(350)));
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let policy = RetryPolicy::builder()
+            .base_delay(Duration::from_millis(1000))
+            .jitter(0.5)
+            .build();
+        for _ in 0..100 {
+            let delay = policy.next_delay(1, &api_error(503)).unwrap();
+            assert!(delay >= Duration::from_millis(500));
+            assert!(delay <= Duration::from_millis(1000));
+        }
+    }
+
+    #[test]
+    fn test_rate_limit_waits_until_reset() {
+        let policy = RetryPolicy::builder().jitter(0.0).build();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let err = rate_limit_error(Some(now + 5));
+        let delay = policy.next_delay(1, &err).unwrap();
+        assert!(delay >= Duration::from_secs(4));
+        assert!(delay <= Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_rate_limit_wait_is_capped() {
+        let policy = RetryPolicy::builder()
+            .max_rate_limit_wait(Duration::from_secs(60))
+            .build();
+        let err = rate_limit_error(Some(u64::MAX));
+        assert_eq!(policy.next_delay(1, &err), None);
+
+        let err = Error::RateLimit {
+            reset_at: None,
+            retry_after: Some(Duration::from_secs(60)),
+            request_id: None,
+        };
+        assert_eq!(policy.next_delay(1, &err), Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_default_statuses_match_is_retryable() {
+        let policy = RetryPolicy::default();
+        for status in 400..600 {
+            let err = api_error(status);
+            assert_eq!(policy.should_retry(&err), err.is_retryable(), "{}", status);
+        }
+    }
+
+    #[test]
+    fn test_rate_limit_reset_in_past_uses_backoff() {
+        let policy = RetryPolicy::builder().jitter(0.0).build();
+        let err = rate_limit_error(Some(1));
+        assert_eq!(policy.next_delay(1, &err), Some(DEFAULT_BASE_DELAY));
+    }
+
+    #[test]
+    fn test_max_attempts_minimum_is_one() {
+        let policy = RetryPolicy::builder().max_attempts(0).build();
+        assert_eq!(policy.max_attempts, 1);
+        assert!(policy.next_delay(1, &api_error(503)).is_none());
+    }
+
+    #[test]
+    fn test_policy_is_debug() {
+        let policy = RetryPolicy::builder().on_retry(|_| {}).build();
+        let debug_str = format!("{:?}", policy);
+        assert!(debug_str.contains("RetryPolicy"));
+        assert!(debug_str.contains("on_retry: true"));
+    }
+}
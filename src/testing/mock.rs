@@ -0,0 +1,327 @@
+//! In-memory mock of the API.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use futures::future::{self, BoxFuture, FutureExt};
+
+use crate::api::EarningsFeedApi;
+use crate::error::{Error, Result};
+use crate::models::{
+    Company, CompanySearchResult, Filing, FilingDetail, InsiderTransaction, InstitutionalHolding,
+    ListFilingsParams, ListInsiderParams, ListInstitutionalParams, PaginatedResponse,
+    SearchCompaniesParams,
+};
+
+/// A call made to a [`MockEarningsFeed`], with its arguments.
+#[derive(Debug, Clone)]
+pub enum MockCall {
+    /// [`EarningsFeedApi::list_filings`].
+    ListFilings(ListFilingsParams),
+    /// [`EarningsFeedApi::get_filing`].
+    GetFiling(String),
+    /// [`EarningsFeedApi::list_insider`].
+    ListInsider(ListInsiderParams),
+    /// [`EarningsFeedApi::list_institutional`].
+    ListInstitutional(ListInstitutionalParams),
+    /// [`EarningsFeedApi::search_companies`].
+    SearchCompanies(SearchCompaniesParams),
+    /// [`EarningsFeedApi::get_company`].
+    GetCompany(u64),
+}
+
+/// In-memory implementation of [`EarningsFeedApi`].
+///
+/// List and search calls serve the queued pages of their endpoint in
+/// order, one page per call, and an empty page once the queue is
+/// exhausted. Lookups serve the registered filing or company, or fail
+/// with [`Error::NotFound`]. Errors queued with
+/// [`fail_next`](Self::fail_next) are returned by the next calls instead,
+/// whatever the endpoint.
+///
+/// Every call is recorded and can be inspected with
+/// [`calls`](Self::calls).
+///
+/// # Example
+///
+/// ```rust
+/// use earningsfeed::testing::{MockCall, MockEarningsFeed};
+/// use earningsfeed::{
+///     CompanySearchResult, EarningsFeedApi, PaginatedResponse, SearchCompaniesParams,
+/// };
+///
+/// # futures::executor::block_on(async {
+/// let mock = MockEarningsFeed::new().company_search(PaginatedResponse {
+///     items: vec![CompanySearchResult {
+///         cik: 320193,
+///         name: "Apple Inc.".into(),
+///         ticker: Some("AAPL".into()),
+///         exchange: None,
+///         entity_type: None,
+///         category: None,
+///         sic_code: None,
+///         sic_description: None,
+///         logo_url: None,
+///     }],
+///     next_cursor: None,
+///     has_more: false,
+/// });
+///
+/// let params = SearchCompaniesParams::builder().q("apple").build();
+/// let page = mock.search_companies(&params).await.unwrap();
+/// assert_eq!(page.items[0].cik, 320193);
+/// assert!(matches!(&mock.calls()[..], [MockCall::SearchCompanies(_)]));
+/// # });
+/// ```
+#[derive(Debug, Default)]
+pub struct MockEarningsFeed {
+    state: Mutex<MockState>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    filings: VecDeque<PaginatedResponse<Filing>>,
+    filing_details: HashMap<String, FilingDetail>,
+    insider: VecDeque<PaginatedResponse<InsiderTransaction>>,
+    institutional: VecDeque<PaginatedResponse<InstitutionalHolding>>,
+    company_searches: VecDeque<PaginatedResponse<CompanySearchResult>>,
+    companies: HashMap<u64, Company>,
+    errors: VecDeque<Error>,
+    calls: Vec<MockCall>,
+}
+
+impl MockEarningsFeed {
+    /// Create a mock with no canned responses.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a page of filings.
+    #[must_use]
+    pub fn filings(self, page: PaginatedResponse<Filing>) -> Self {
+        self.lock().filings.push_back(page);
+        self
+    }
+
+    /// Register a filing served by accession number.
+    #[must_use]
+    pub fn filing(self, filing: FilingDetail) -> Self {
+        self.lock()
+            .filing_details
+            .insert(filing.accession_number.clone(), filing);
+        self
+    }
+
+    /// Queue a page of insider transactions.
+    #[must_use]
+    pub fn insider(self, page: PaginatedResponse<InsiderTransaction>) -> Self {
+        self.lock().insider.push_back(page);
+        self
+    }
+
+    /// Queue a page of institutional holdings.
+    #[must_use]
+    pub fn institutional(self, page: PaginatedResponse<InstitutionalHolding>) -> Self {
+        self.lock().institutional.push_back(page);
+        self
+    }
+
+    /// Queue a page of company search results.
+    #[must_use]
+    pub fn company_search(self, page: PaginatedResponse<CompanySearchResult>) -> Self {
+        self.lock().company_searches.push_back(page);
+        self
+    }
+
+    /// Register a company served by CIK.
+    #[must_use]
+    pub fn company(self, company: Company) -> Self {
+        self.lock().companies.insert(company.cik, company);
+        self
+    }
+
+    /// Queue an error returned by the next call.
+    #[must_use]
+    pub fn fail_next(self, error: Error) -> Self {
+        self.lock().errors.push_back(error);
+        self
+    }
+
+    /// The calls made so far, in order.
+    #[must_use]
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.lock().calls.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Record a call and compute its response.
+    fn respond<T>(&self, call: MockCall, f: impl FnOnce(&mut MockState) -> Result<T>) -> Result<T> {
+        let mut state = self.lock();
+        state.calls.push(call);
+        match state.errors.pop_front() {
+            Some(error) => Err(error),
+            None => f(&mut state),
+        }
+    }
+}
+
+fn not_found(path: String) -> Error {
+    Error::NotFound {
+        path,
+        request_id: None,
+    }
+}
+
+impl EarningsFeedApi for MockEarningsFeed {
+    fn list_filings<'a>(
+        &'a self,
+        params: &'a ListFilingsParams,
+    ) -> BoxFuture<'a, Result<PaginatedResponse<Filing>>> {
+        let result = self.respond(MockCall::ListFilings(params.clone()), |state| {
+            Ok(state.filings.pop_front().unwrap_or_default())
+        });
+        future::ready(result).boxed()
+    }
+
+    fn get_filing<'a>(&'a self, accession_number: &'a str) -> BoxFuture<'a, Result<FilingDetail>> {
+        let call = MockCall::GetFiling(accession_number.to_string());
+        let result = self.respond(call, |state| {
+            state
+                .filing_details
+                .get(accession_number)
+                .cloned()
+                .ok_or_else(|| not_found(format!("/api/v1/filings/{}", accession_number)))
+        });
+        future::ready(result).boxed()
+    }
+
+    fn list_insider<'a>(
+        &'a self,
+        params: &'a ListInsiderParams,
+    ) -> BoxFuture<'a, Result<PaginatedResponse<InsiderTransaction>>> {
+        let result = self.respond(MockCall::ListInsider(params.clone()), |state| {
+            Ok(state.insider.pop_front().unwrap_or_default())
+        });
+        future::ready(result).boxed()
+    }
+
+    fn list_institutional<'a>(
+        &'a self,
+        params: &'a ListInstitutionalParams,
+    ) -> BoxFuture<'a, Result<PaginatedResponse<InstitutionalHolding>>> {
+        let result = self.respond(MockCall::ListInstitutional(params.clone()), |state| {
+            Ok(state.institutional.pop_front().unwrap_or_default())
+        });
+        future::ready(result).boxed()
+    }
+
+    fn search_companies<'a>(
+        &'a self,
+        params: &'a SearchCompaniesParams,
+    ) -> BoxFuture<'a, Result<PaginatedResponse<CompanySearchResult>>> {
+        let result = self.respond(MockCall::SearchCompanies(params.clone()), |state| {
+            Ok(state.company_searches.pop_front().unwrap_or_default())
+        });
+        future::ready(result).boxed()
+    }
+
+    fn get_company(&self, cik: u64) -> BoxFuture<'_, Result<Company>> {
+        let result = self.respond(MockCall::GetCompany(cik), |state| {
+            state
+                .companies
+                .get(&cik)
+                .cloned()
+                .ok_or_else(|| not_found(format!("/api/v1/companies/{}", cik)))
+        });
+        future::ready(result).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search_result(cik: u64, name: &str) -> CompanySearchResult {
+        CompanySearchResult {
+            cik,
+            name: name.to_string(),
+            ticker: None,
+            exchange: None,
+            entity_type: None,
+            category: None,
+            sic_code: None,
+            sic_description: None,
+            logo_url: None,
+        }
+    }
+
+    /// Code under test: collects all search results by following cursors.
+    async fn all_names(api: &dyn EarningsFeedApi) -> Result<Vec<String>> {
+        let mut params = SearchCompaniesParams::builder().q("bank").build();
+        let mut names = Vec::new();
+        loop {
+            let page = api.search_companies(&params).await?;
+            names.extend(page.items.into_iter().map(|c| c.name));
+            match page.next_cursor {
+                Some(cursor) if page.has_more => params.cursor = Some(cursor),
+                _ => return Ok(names),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serves_pages_in_order_and_records_calls() {
+        let mock = MockEarningsFeed::new()
+            .company_search(PaginatedResponse {
+                items: vec![search_result(1, "First Bank")],
+                next_cursor: Some("page2".to_string()),
+                has_more: true,
+            })
+            .company_search(PaginatedResponse {
+                items: vec![search_result(2, "Second Bank")],
+                next_cursor: None,
+                has_more: false,
+            });
+
+        let names = all_names(&mock).await.unwrap();
+        assert_eq!(names, vec!["First Bank", "Second Bank"]);
+
+        let cursors: Vec<_> = mock
+            .calls()
+            .into_iter()
+            .map(|call| match call {
+                MockCall::SearchCompanies(params) => params.cursor,
+                other => panic!("unexpected call {:?}", other),
+            })
+            .collect();
+        assert_eq!(cursors, vec![None, Some("page2".to_string())]);
+
+        // Exhausted queues serve empty pages.
+        assert!(all_names(&mock).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_lookups_and_injected_errors() {
+        let mock = MockEarningsFeed::new().fail_next(Error::Timeout {
+            timeout: std::time::Duration::from_secs(30),
+            source: None,
+        });
+
+        assert!(matches!(
+            mock.get_company(320193).await,
+            Err(Error::Timeout { .. })
+        ));
+        match mock.get_company(320193).await {
+            Err(Error::NotFound { path, .. }) => assert_eq!(path, "/api/v1/companies/320193"),
+            other => panic!("expected not found, got {:?}", other),
+        }
+        assert!(matches!(
+            mock.calls().as_slice(),
+            [MockCall::GetCompany(320193), MockCall::GetCompany(320193)]
+        ));
+    }
+}
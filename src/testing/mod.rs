@@ -0,0 +1,37 @@
+//! Test doubles for code built on the EarningsFeed client.
+//!
+//! Enabled with the `testing` feature. Two doubles are provided:
+//!
+//! - [`MockEarningsFeed`] implements [`EarningsFeedApi`](crate::EarningsFeedApi)
+//!   in memory: it serves canned responses and records every call, so code
+//!   written against the trait can be unit-tested without an HTTP server.
+//! - [`FakeServer`] is an in-process HTTP server implementing the API's
+//!   endpoints over a set of [`Fixtures`], with real filtering, cursor
+//!   pagination and fault injection, for integration tests of code that
+//!   uses the real [`EarningsFeed`](crate::EarningsFeed) client.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use earningsfeed::testing::{FakeServer, Fault};
+//! use earningsfeed::ListFilingsParams;
+//!
+//! #[tokio::test]
+//! async fn lists_annual_reports() {
+//!     let server = FakeServer::start().await;
+//!     let client = server.client();
+//!
+//!     let params = ListFilingsParams::builder().ticker("AAPL").forms(vec!["10-K"]).build();
+//!     let page = client.filings().list(&params).await.unwrap();
+//!     assert_eq!(page.items[0].form_type, "10-K");
+//!
+//!     server.fail_next(Fault::ServerError(503));
+//!     assert!(client.filings().list(&params).await.is_err());
+//! }
+//! ```
+
+mod mock;
+mod server;
+
+pub use mock::{MockCall, MockEarningsFeed};
+pub use server::{FakeServer, Fault, Fixtures, FAKE_API_KEY};
@@ -0,0 +1,802 @@
+//! In-process fake of the EarningsFeed API.
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wiremock::matchers::{method, path_regex};
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+use crate::client::EarningsFeed;
+use crate::config::ClientConfigBuilder;
+use crate::error::{Error, Result};
+use crate::models::{
+    AcquiredDisposed, Company, CompanySearchResult, Filing, FilingDetail, FilingDocument,
+    FilingRole, InsiderTransaction, InstitutionalHolding, PutCall,
+};
+
+/// API key accepted by the clients returned by [`FakeServer::client`].
+///
+/// The server accepts any non-empty bearer token.
+pub const FAKE_API_KEY: &str = "fake_api_key";
+
+/// Default number of items per page.
+const DEFAULT_LIMIT: usize = 25;
+
+/// Maximum number of items per page.
+const MAX_LIMIT: usize = 100;
+
+/// Records served by a [`FakeServer`].
+///
+/// Filing details are derived from the filings, and company search
+/// results from the companies.
+#[derive(Debug, Clone, Default)]
+pub struct Fixtures {
+    /// Filings, served by the filings endpoints.
+    pub filings: Vec<Filing>,
+    /// Insider transactions.
+    pub insider: Vec<InsiderTransaction>,
+    /// Institutional holdings.
+    pub institutional: Vec<InstitutionalHolding>,
+    /// Company profiles, served by the company lookup and search endpoints.
+    pub companies: Vec<Company>,
+}
+
+impl Fixtures {
+    /// The fixtures bundled with the crate.
+    ///
+    /// A small, realistic data set around five large issuers (Apple,
+    /// Microsoft, NVIDIA, JPMorgan Chase and Tesla): annual, quarterly and
+    /// current reports, Form 4 transactions of their executives, and 13F
+    /// positions of large managers, including option positions.
+    #[must_use]
+    pub fn bundled() -> Self {
+        fn parse<T: DeserializeOwned>(json: &str) -> Vec<T> {
+            serde_json::from_str(json).expect("bundled fixtures are valid")
+        }
+
+        Self {
+            filings: parse(include_str!("data/filings.json")),
+            insider: parse(include_str!("data/insider.json")),
+            institutional: parse(include_str!("data/institutional.json")),
+            companies: parse(include_str!("data/companies.json")),
+        }
+    }
+
+    /// Load fixtures from a directory.
+    ///
+    /// Reads JSON arrays of records from `filings.json`, `insider.json`,
+    /// `institutional.json` and `companies.json`, in the API's format.
+    /// Missing files leave the corresponding records empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if a file cannot be read or parsed.
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        fn load<T: DeserializeOwned>(dir: &Path, name: &str) -> Result<Vec<T>> {
+            let path = dir.join(name);
+            match std::fs::read(&path) {
+                Ok(json) => serde_json::from_slice(&json).map_err(|e| {
+                    Error::Config(format!("invalid fixtures in {}: {}", path.display(), e))
+                }),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+                Err(e) => Err(Error::Config(format!(
+                    "cannot read fixtures from {}: {}",
+                    path.display(),
+                    e
+                ))),
+            }
+        }
+
+        let dir = dir.as_ref();
+        Ok(Self {
+            filings: load(dir, "filings.json")?,
+            insider: load(dir, "insider.json")?,
+            institutional: load(dir, "institutional.json")?,
+            companies: load(dir, "companies.json")?,
+        })
+    }
+}
+
+/// A fault injected into a [`FakeServer`] response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// Respond `429 Too Many Requests`, with `Retry-After` and
+    /// `X-RateLimit-Reset` headers for a limit resetting after the delay.
+    RateLimited {
+        /// Time until the rate limit resets.
+        reset_after: Duration,
+    },
+    /// Respond with the given server error status, e.g. 503.
+    ServerError(u16),
+    /// Serve the normal response after a delay.
+    Delay(Duration),
+}
+
+/// An in-process HTTP server implementing the EarningsFeed API.
+///
+/// The server serves the list, lookup and search endpoints of filings,
+/// insider transactions, institutional holdings and companies from a set
+/// of [`Fixtures`]. List endpoints apply the same filters as the API
+/// (ticker, CIK, forms, dates, direction, minimum value, ...), return
+/// items newest first and paginate with cursors; `limit` defaults to 25
+/// and must be between 1 and 100.
+///
+/// Requests without a bearer token are rejected with `401`. Faults queued
+/// with [`fail_next`](Self::fail_next) apply to the next requests, in
+/// order, and [`set_latency`](Self::set_latency) delays every response.
+///
+/// The server stops when dropped.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use earningsfeed::testing::FakeServer;
+/// use earningsfeed::{ListInsiderParams, TransactionDirection};
+///
+/// let server = FakeServer::start().await;
+/// let client = server.client();
+///
+/// let params = ListInsiderParams::builder()
+///     .ticker("NVDA")
+///     .direction(TransactionDirection::Sell)
+///     .min_value(10_000_000)
+///     .build();
+/// let sales = client.insider().list(&params).await?;
+/// ```
+pub struct FakeServer {
+    server: MockServer,
+    state: Arc<FakeState>,
+}
+
+impl FakeServer {
+    /// Start a server serving the [bundled fixtures](Fixtures::bundled).
+    pub async fn start() -> Self {
+        Self::with_fixtures(Fixtures::bundled()).await
+    }
+
+    /// Start a server serving the given fixtures.
+    pub async fn with_fixtures(mut fixtures: Fixtures) -> Self {
+        fixtures.filings.sort_by_key(|f| Reverse(f.sorted_at));
+        fixtures.insider.sort_by_key(|t| Reverse(t.filed_at));
+        fixtures.institutional.sort_by_key(|h| Reverse(h.filed_at));
+
+        let state = Arc::new(FakeState {
+            fixtures,
+            faults: Mutex::new(VecDeque::new()),
+            latency: Mutex::new(Duration::ZERO),
+            requests: AtomicU64::new(0),
+        });
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path_regex("^/api/v1/"))
+            .respond_with(Responder(state.clone()))
+            .mount(&server)
+            .await;
+
+        Self { server, state }
+    }
+
+    /// The base URL of the server.
+    #[must_use]
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// A config builder pointing at the server, with [`FAKE_API_KEY`].
+    ///
+    /// Use it to test the client with retries, caching or other options.
+    #[must_use]
+    pub fn config(&self) -> ClientConfigBuilder {
+        EarningsFeed::builder()
+            .api_key(FAKE_API_KEY)
+            .base_url(self.uri())
+    }
+
+    /// A client pointing at the server, with the default configuration.
+    #[must_use]
+    pub fn client(&self) -> EarningsFeed {
+        self.config()
+            .build()
+            .and_then(EarningsFeed::with_config)
+            .expect("fake server client config is valid")
+    }
+
+    /// Inject a fault into the next request that has none queued yet.
+    pub fn fail_next(&self, fault: Fault) {
+        lock(&self.state.faults).push_back(fault);
+    }
+
+    /// Delay every response by the given latency.
+    pub fn set_latency(&self, latency: Duration) {
+        *lock(&self.state.latency) = latency;
+    }
+
+    /// Number of requests received so far.
+    #[must_use]
+    pub fn request_count(&self) -> u64 {
+        self.state.requests.load(Ordering::Relaxed)
+    }
+}
+
+impl std::fmt::Debug for FakeServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FakeServer")
+            .field("uri", &self.uri())
+            .field("request_count", &self.request_count())
+            .finish()
+    }
+}
+
+struct FakeState {
+    fixtures: Fixtures,
+    faults: Mutex<VecDeque<Fault>>,
+    latency: Mutex<Duration>,
+    requests: AtomicU64,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+struct Responder(Arc<FakeState>);
+
+impl Respond for Responder {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let state = &self.0;
+        let number = state.requests.fetch_add(1, Ordering::Relaxed) + 1;
+        let fault = lock(&state.faults).pop_front();
+        let mut delay = *lock(&state.latency);
+
+        let response = match fault {
+            Some(Fault::RateLimited { reset_after }) => rate_limited(reset_after),
+            Some(Fault::ServerError(status)) => {
+                error(status, "Internal Server Error", "server error")
+            }
+            Some(Fault::Delay(extra)) => {
+                delay += extra;
+                state.route(request)
+            }
+            None => state.route(request),
+        };
+
+        response
+            .insert_header("X-Request-Id", format!("req_fake_{}", number).as_str())
+            .set_delay(delay)
+    }
+}
+
+impl FakeState {
+    fn route(&self, request: &Request) -> ResponseTemplate {
+        let authorized = request
+            .headers
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|key| !key.trim().is_empty());
+        if !authorized {
+            return error(401, "Unauthorized", "invalid or missing API key");
+        }
+
+        let query = Query(request.url.query_pairs().into_owned().collect());
+        let path = request.url.path().trim_end_matches('/');
+        let result = match path.strip_prefix("/api/v1/") {
+            Some("filings") => self.list_filings(&query),
+            Some("insider/transactions") => self.list_insider(&query),
+            Some("institutional/holdings") => self.list_institutional(&query),
+            Some("companies/search") => self.search_companies(&query),
+            Some(rest) => match rest.split_once('/') {
+                Some(("filings", accession)) => self.get_filing(accession),
+                Some(("companies", cik)) => self.get_company(cik),
+                _ => Err(not_found(path)),
+            },
+            None => Err(not_found(path)),
+        };
+        result.unwrap_or_else(|response| *response)
+    }
+
+    fn list_filings(&self, query: &Query) -> Reply {
+        let ticker = query.get("ticker");
+        let cik = query.parse::<u64>("cik")?;
+        let forms = query.list("forms");
+        let provisional = match query.get("status") {
+            None | Some("all") => None,
+            Some("provisional") => Some(true),
+            Some("final") => Some(false),
+            Some(_) => return Err(invalid("status", "must be one of all, provisional, final")),
+        };
+        let dates = query.date_range()?;
+        let q = query.get("q").map(str::to_lowercase);
+
+        let items = self.fixtures.filings.iter().filter(|f| {
+            ticker.map_or(true, |t| eq(f.primary_ticker.as_deref(), t))
+                && cik.map_or(true, |cik| f.cik == cik)
+                && forms
+                    .as_ref()
+                    .map_or(true, |forms| forms.contains(&f.form_type.as_str()))
+                && provisional.map_or(true, |p| f.provisional == p)
+                && dates.contains(f.filed_at.date_naive())
+                && q.as_ref().map_or(true, |q| {
+                    f.title.to_lowercase().contains(q)
+                        || f.company_name
+                            .as_ref()
+                            .is_some_and(|n| n.to_lowercase().contains(q))
+                })
+        });
+        page(items, query)
+    }
+
+    fn get_filing(&self, accession_number: &str) -> Reply {
+        let filing = self
+            .fixtures
+            .filings
+            .iter()
+            .find(|f| f.accession_number == accession_number)
+            .ok_or_else(|| not_found(&format!("/api/v1/filings/{}", accession_number)))?;
+        Ok(json(200, &filing_detail(filing)))
+    }
+
+    fn list_insider(&self, query: &Query) -> Reply {
+        let ticker = query.get("ticker");
+        let cik = query.parse::<u64>("cik")?;
+        let person_cik = query.parse::<u64>("personCik")?;
+        let acquired = match query.get("direction") {
+            None => None,
+            Some("buy") => Some(AcquiredDisposed::A),
+            Some("sell") => Some(AcquiredDisposed::D),
+            Some(_) => return Err(invalid("direction", "must be one of buy, sell")),
+        };
+        let codes = query.list("codes");
+        let derivative = query.parse::<bool>("derivative")?;
+        let min_value = query.parse::<u64>("minValue")?.map(Decimal::from);
+        let dates = query.date_range()?;
+
+        let items = self.fixtures.insider.iter().filter(|t| {
+            ticker.map_or(true, |ticker| eq(t.ticker.as_deref(), ticker))
+                && cik.map_or(true, |cik| t.company_cik == cik)
+                && person_cik.map_or(true, |cik| t.person_cik == cik)
+                && acquired.map_or(true, |a| t.acquired_disposed == a)
+                && codes
+                    .as_ref()
+                    .map_or(true, |c| c.contains(&t.transaction_code.as_str()))
+                && derivative.map_or(true, |d| t.is_derivative == d)
+                && min_value.map_or(true, |min| t.transaction_value.is_some_and(|v| v >= min))
+                && dates.contains(t.transaction_date)
+        });
+        page(items, query)
+    }
+
+    fn list_institutional(&self, query: &Query) -> Reply {
+        let cik = query.parse::<u64>("cik")?;
+        let ticker = query.get("ticker");
+        let cusip = query.get("cusip");
+        let manager_cik = query.parse::<u64>("managerCik")?;
+        let min_value = query.parse::<u64>("minValue")?.map(Decimal::from);
+        let put_call = match query.get("putCall") {
+            None => None,
+            Some("put") => Some(Some(PutCall::Put)),
+            Some("call") => Some(Some(PutCall::Call)),
+            Some("equity") => Some(None),
+            Some(_) => return Err(invalid("putCall", "must be one of put, call, equity")),
+        };
+        let report_period = query.date("reportPeriod")?;
+
+        let items = self.fixtures.institutional.iter().filter(|h| {
+            cik.map_or(true, |cik| h.company_cik == Some(cik))
+                && ticker.map_or(true, |t| eq(h.ticker.as_deref(), t))
+                && cusip.map_or(true, |c| h.cusip.eq_ignore_ascii_case(c))
+                && manager_cik.map_or(true, |cik| h.manager_cik == cik)
+                && min_value.map_or(true, |min| h.value >= min)
+                && put_call.map_or(true, |pc| h.put_call == pc)
+                && report_period.map_or(true, |date| h.report_period_date == date)
+        });
+        page(items, query)
+    }
+
+    fn search_companies(&self, query: &Query) -> Reply {
+        let q = query.get("q").map(str::to_lowercase);
+        let ticker = query.get("ticker");
+        let sic_code = query.parse::<u32>("sicCode")?;
+        let state = query.get("state");
+
+        let results: Vec<_> = self
+            .fixtures
+            .companies
+            .iter()
+            .filter(|c| {
+                q.as_ref().map_or(true, |q| {
+                    c.name.to_lowercase().contains(q) || eq(c.primary_ticker.as_deref(), q)
+                }) && ticker.map_or(true, |t| {
+                    c.tickers.iter().any(|tk| tk.symbol.eq_ignore_ascii_case(t))
+                }) && sic_code.map_or(true, |code| c.sic_codes.iter().any(|s| s.code == code))
+                    && state.map_or(true, |s| eq(c.state_of_incorporation.as_deref(), s))
+            })
+            .map(search_result)
+            .collect();
+        page(results.iter(), query)
+    }
+
+    fn get_company(&self, cik: &str) -> Reply {
+        let path = format!("/api/v1/companies/{}", cik);
+        let cik = u64::from_str(cik).map_err(|_| not_found(&path))?;
+        let company = self
+            .fixtures
+            .companies
+            .iter()
+            .find(|c| c.cik == cik)
+            .ok_or_else(|| not_found(&path))?;
+        Ok(json(200, company))
+    }
+}
+
+/// An error response.
+type Rejection = Box<ResponseTemplate>;
+
+/// A response, or an error response.
+type Reply = std::result::Result<ResponseTemplate, Rejection>;
+
+/// Query parameters of a request.
+struct Query(HashMap<String, String>);
+
+impl Query {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .get(name)
+            .map(String::as_str)
+            .filter(|v| !v.is_empty())
+    }
+
+    fn parse<T: FromStr>(&self, name: &str) -> std::result::Result<Option<T>, Rejection> {
+        self.get(name)
+            .map(|value| value.parse().map_err(|_| invalid(name, "is malformed")))
+            .transpose()
+    }
+
+    fn list(&self, name: &str) -> Option<Vec<&str>> {
+        self.get(name)
+            .map(|value| value.split(',').map(str::trim).collect())
+    }
+
+    fn date(&self, name: &str) -> std::result::Result<Option<NaiveDate>, Rejection> {
+        self.get(name)
+            .map(|value| {
+                NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .map_err(|_| invalid(name, "must be a date (YYYY-MM-DD)"))
+            })
+            .transpose()
+    }
+
+    fn date_range(&self) -> std::result::Result<DateRange, Rejection> {
+        Ok(DateRange {
+            start: self.date("startDate")?,
+            end: self.date("endDate")?,
+        })
+    }
+}
+
+/// Inclusive date range of the `startDate` and `endDate` parameters.
+struct DateRange {
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+}
+
+impl DateRange {
+    fn contains(&self, date: NaiveDate) -> bool {
+        self.start.map_or(true, |start| date >= start) && self.end.map_or(true, |end| date <= end)
+    }
+}
+
+/// Case-insensitive comparison of an optional field with a filter value.
+fn eq(field: Option<&str>, value: &str) -> bool {
+    field.is_some_and(|field| field.eq_ignore_ascii_case(value))
+}
+
+/// Respond with one page of the items, according to `limit` and `cursor`.
+fn page<'a, T: Serialize + 'a>(items: impl Iterator<Item = &'a T>, query: &Query) -> Reply {
+    let limit = query.parse::<usize>("limit")?.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(invalid("limit", "must be between 1 and 100"));
+    }
+    let offset = match query.get("cursor") {
+        Some(cursor) => cursor
+            .strip_prefix("fake_")
+            .and_then(|offset| offset.parse().ok())
+            .ok_or_else(|| invalid("cursor", "is not a valid cursor"))?,
+        None => 0,
+    };
+
+    let items: Vec<_> = items.skip(offset).take(limit + 1).collect();
+    let has_more = items.len() > limit;
+    let items = &items[..items.len().min(limit)];
+    Ok(json(
+        200,
+        &serde_json::json!({
+            "items": items,
+            "nextCursor": has_more.then(|| format!("fake_{}", offset + limit)),
+            "hasMore": has_more,
+        }),
+    ))
+}
+
+fn filing_detail(filing: &Filing) -> FilingDetail {
+    let no_dashes = filing
+        .accession_no_dashes
+        .clone()
+        .unwrap_or_else(|| filing.accession_number.replace('-', ""));
+    FilingDetail {
+        accession_number: filing.accession_number.clone(),
+        accession_no_dashes: Some(no_dashes.clone()),
+        cik: filing.cik,
+        form_type: filing.form_type.clone(),
+        filed_at: filing.filed_at,
+        accept_ts: filing.accept_ts,
+        provisional: filing.provisional,
+        feed_day: filing.feed_day.clone(),
+        title: filing.title.clone(),
+        url: filing.url.clone(),
+        size_bytes: filing.size_bytes,
+        sec_relative_dir: Some(format!("edgar/data/{}/{}", filing.cik, no_dashes)),
+        company_name: filing.company_name.clone(),
+        primary_ticker: filing.primary_ticker.clone(),
+        company: filing.company.clone(),
+        documents: vec![FilingDocument {
+            seq: 1,
+            filename: format!("{}.htm", filing.accession_number),
+            doc_type: filing.form_type.clone(),
+            description: Some(filing.title.clone()),
+            is_primary: true,
+        }],
+        roles: vec![FilingRole {
+            cik: filing.cik,
+            role: "filer".to_string(),
+        }],
+    }
+}
+
+fn search_result(company: &Company) -> CompanySearchResult {
+    let primary = company
+        .tickers
+        .iter()
+        .find(|t| t.is_primary)
+        .or(company.tickers.first());
+    let sic = company.sic_codes.first();
+    CompanySearchResult {
+        cik: company.cik,
+        name: company.name.clone(),
+        ticker: company.primary_ticker.clone(),
+        exchange: primary.map(|t| t.exchange.clone()),
+        entity_type: company.entity_type.clone(),
+        category: company.category.clone(),
+        sic_code: sic.map(|s| s.code),
+        sic_description: sic.map(|s| s.description.clone()),
+        logo_url: company.logo_url.clone(),
+    }
+}
+
+fn json<T: Serialize + ?Sized>(status: u16, body: &T) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_json(body)
+}
+
+fn error(status: u16, error: &str, message: &str) -> ResponseTemplate {
+    json(
+        status,
+        &serde_json::json!({ "error": error, "message": message }),
+    )
+}
+
+fn invalid(param: &str, reason: &str) -> Rejection {
+    Box::new(json(
+        400,
+        &serde_json::json!({
+            "error": "Bad Request",
+            "message": format!("{} {}", param, reason),
+            "code": "validation_error",
+            "details": [{ "field": param, "reason": reason }],
+        }),
+    ))
+}
+
+fn not_found(path: &str) -> Rejection {
+    Box::new(error(404, "Not Found", &format!("{} was not found", path)))
+}
+
+fn rate_limited(reset_after: Duration) -> ResponseTemplate {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let reset_at = (now + reset_after).as_secs_f64().ceil() as u64;
+    let retry_after = reset_after.as_secs_f64().ceil() as u64;
+    error(429, "Too Many Requests", "rate limit exceeded")
+        .insert_header("Retry-After", retry_after.to_string().as_str())
+        .insert_header("X-RateLimit-Limit", "60")
+        .insert_header("X-RateLimit-Remaining", "0")
+        .insert_header("X-RateLimit-Reset", reset_at.to_string().as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        ListFilingsParams, ListInsiderParams, ListInstitutionalParams, PutCallFilter,
+        SearchCompaniesParams, TransactionDirection,
+    };
+    use crate::retry::RetryPolicy;
+    use futures::TryStreamExt;
+
+    #[test]
+    fn test_bundled_fixtures_parse() {
+        let fixtures = Fixtures::bundled();
+        assert_eq!(fixtures.companies.len(), 5);
+        assert!(!fixtures.filings.is_empty());
+        assert!(!fixtures.insider.is_empty());
+        assert!(!fixtures.institutional.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_filters_and_paginates_filings() {
+        let server = FakeServer::start().await;
+        let client = server.client();
+
+        let params = ListFilingsParams::builder()
+            .forms(vec!["10-K", "10-Q"])
+            .start_date(NaiveDate::from_ymd_opt(2024, 7, 1).unwrap())
+            .limit(2)
+            .build();
+        let filings: Vec<_> = client.filings().iter(params).try_collect().await.unwrap();
+
+        assert_eq!(filings.len(), 6);
+        assert!(filings.windows(2).all(|w| w[0].sorted_at >= w[1].sorted_at));
+        assert!(filings.iter().all(|f| f.form_type.starts_with("10-")));
+        assert_eq!(server.request_count(), 3);
+
+        let detail = client
+            .filings()
+            .get(&filings[0].accession_number)
+            .await
+            .unwrap();
+        assert_eq!(detail.roles[0].role, "filer");
+
+        let params = ListFilingsParams::builder().ticker("tsla").build();
+        let page = client.filings().list(&params).await.unwrap();
+        assert_eq!(page.items.len(), 3);
+        assert!(page.items[0].provisional);
+    }
+
+    #[tokio::test]
+    async fn test_filters_insider_institutional_and_companies() {
+        let server = FakeServer::start().await;
+        let client = server.client();
+
+        let params = ListInsiderParams::builder()
+            .ticker("NVDA")
+            .direction(TransactionDirection::Sell)
+            .min_value(20_000_000)
+            .build();
+        let sales = client.insider().list(&params).await.unwrap();
+        assert_eq!(sales.items.len(), 1);
+        assert_eq!(sales.items[0].transaction_date.to_string(), "2024-09-04");
+
+        let params = ListInstitutionalParams::builder()
+            .put_call(PutCallFilter::Put)
+            .build();
+        let puts = client.institutional().list(&params).await.unwrap();
+        assert_eq!(puts.items.len(), 1);
+        assert_eq!(puts.items[0].ticker.as_deref(), Some("TSLA"));
+
+        let params = SearchCompaniesParams::builder().state("DE").build();
+        let page = client.companies().search(&params).await.unwrap();
+        let names: Vec<_> = page.items.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["NVIDIA Corporation", "JPMorgan Chase & Co."]);
+
+        assert_eq!(
+            client.companies().get(320193).await.unwrap().name,
+            "Apple Inc."
+        );
+        assert!(matches!(
+            client.companies().get(1).await,
+            Err(Error::NotFound { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_invalid_requests() {
+        let server = FakeServer::start().await;
+
+        let params = ListFilingsParams::builder().limit(500).build();
+        match server.client().filings().list(&params).await {
+            Err(Error::Validation { context, .. }) => {
+                assert_eq!(context.details[0].field.as_deref(), Some("limit"));
+            }
+            other => panic!("expected validation error, got {:?}", other),
+        }
+
+        let client = EarningsFeed::builder()
+            .api_key(" ")
+            .base_url(server.uri())
+            .build()
+            .and_then(EarningsFeed::with_config)
+            .unwrap();
+        let result = client.filings().list(&ListFilingsParams::default()).await;
+        assert!(matches!(result, Err(Error::Authentication)));
+    }
+
+    #[tokio::test]
+    async fn test_injected_faults() {
+        let server = FakeServer::start().await;
+        let policy = RetryPolicy::builder()
+            .base_delay(Duration::from_millis(1))
+            .jitter(0.0)
+            .build();
+        let client = server
+            .config()
+            .retry_policy(policy)
+            .timeout(Duration::from_millis(200))
+            .build()
+            .and_then(EarningsFeed::with_config)
+            .unwrap();
+        let params = ListFilingsParams::default();
+
+        server.fail_next(Fault::ServerError(503));
+        server.fail_next(Fault::ServerError(502));
+        assert!(client.filings().list(&params).await.is_ok());
+        assert_eq!(client.retry_count(), 2);
+
+        let client = server.client();
+        server.fail_next(Fault::RateLimited {
+            reset_after: Duration::from_secs(30),
+        });
+        match client.filings().list(&params).await {
+            Err(Error::RateLimit { retry_after, .. }) => {
+                assert_eq!(retry_after, Some(Duration::from_secs(30)));
+            }
+            other => panic!("expected rate limit error, got {:?}", other),
+        }
+
+        let client = server
+            .config()
+            .timeout(Duration::from_millis(50))
+            .build()
+            .and_then(EarningsFeed::with_config)
+            .unwrap();
+        server.fail_next(Fault::Delay(Duration::from_millis(500)));
+        assert!(matches!(
+            client.filings().list(&params).await,
+            Err(Error::Timeout { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fixtures_from_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundled = Fixtures::bundled();
+        let companies = serde_json::to_string(&bundled.companies[..1]).unwrap();
+        std::fs::write(dir.path().join("companies.json"), companies).unwrap();
+
+        let fixtures = Fixtures::from_dir(dir.path()).unwrap();
+        assert_eq!(fixtures.companies.len(), 1);
+        assert!(fixtures.filings.is_empty());
+
+        let server = FakeServer::with_fixtures(fixtures).await;
+        let page = server
+            .client()
+            .companies()
+            .search(&SearchCompaniesParams::default())
+            .await
+            .unwrap();
+        assert_eq!(page.items.len(), 1);
+
+        std::fs::write(dir.path().join("filings.json"), "{").unwrap();
+        assert!(matches!(
+            Fixtures::from_dir(dir.path()),
+            Err(Error::Config(_))
+        ));
+    }
+}
@@ -0,0 +1,267 @@
+//! Internal `tracing` instrumentation helpers.
+//!
+//! Every helper compiles to a no-op unless the `tracing` feature is enabled,
+//! so call sites do not need their own `cfg` attributes. The API key is sent
+//! in the `Authorization` header, which is never recorded; query parameters
+//! with credential-like names are redacted as well.
+
+use std::future::Future;
+use std::time::Duration;
+
+use crate::middleware::HttpRequest;
+
+/// Query parameter name fragments whose values are never recorded.
+#[cfg(feature = "tracing")]
+const SENSITIVE_PARAMS: [&str; 5] = ["key", "token", "secret", "password", "auth"];
+
+/// Span covering a single API call, including all retry attempts.
+pub(crate) struct RequestSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+impl RequestSpan {
+    /// Create a span for the given request.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn new(request: &HttpRequest) -> Self {
+        Self {
+            #[cfg(feature = "tracing")]
+            span: tracing::info_span!(
+                "earningsfeed.request",
+                http.method = %request.method,
+                path = %request.path,
+                query = %sanitize_query(&request.query),
+                attempt = tracing::field::Empty,
+                status = tracing::field::Empty,
+                latency_ms = tracing::field::Empty,
+                bytes = tracing::field::Empty,
+            ),
+        }
+    }
+
+    /// Run a future inside this span.
+    pub(crate) async fn run<F: Future>(&self, future: F) -> F::Output {
+        #[cfg(feature = "tracing")]
+        let future = tracing::Instrument::instrument(future, self.span.clone());
+        future.await
+    }
+
+    /// Record the attempt number.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn record_attempt(&self, attempt: u32) {
+        #[cfg(feature = "tracing")]
+        self.span.record("attempt", attempt);
+    }
+
+    /// Record the outcome of an attempt.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn record_response(&self, status: u16, bytes: usize, latency: Duration) {
+        #[cfg(feature = "tracing")]
+        {
+            self.span.record("status", status);
+            self.span.record("bytes", bytes as u64);
+            self.span.record("latency_ms", latency.as_millis() as u64);
+        }
+    }
+}
+
+/// Emit an event for a retry that is about to happen.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn retry(attempt: u32, delay: Duration, error: &crate::error::Error) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(attempt, delay_ms = delay.as_millis() as u64, %error, "retrying request");
+}
+
+/// Emit an event for a change of the circuit breaker's state.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn circuit(event: &crate::circuit_breaker::CircuitEvent) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(
+        from = ?event.from,
+        to = ?event.to,
+        failures = event.failures,
+        "circuit breaker state changed"
+    );
+}
+
+/// Span covering a pagination loop, recording page and item counts.
+pub(crate) struct PaginationSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    #[cfg(feature = "tracing")]
+    pages: u64,
+    #[cfg(feature = "tracing")]
+    items: u64,
+}
+
+impl PaginationSpan {
+    /// Create a span for paginating the given endpoint.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn new(path: &str) -> Self {
+        Self {
+            #[cfg(feature = "tracing")]
+            span: tracing::info_span!(
+                "earningsfeed.paginate",
+                path = %path,
+                pages = 0u64,
+                items = 0u64,
+            ),
+            #[cfg(feature = "tracing")]
+            pages: 0,
+            #[cfg(feature = "tracing")]
+            items: 0,
+        }
+    }
+
+    /// Run a page request inside this span.
+    pub(crate) async fn run<F: Future>(&self, future: F) -> F::Output {
+        #[cfg(feature = "tracing")]
+        let future = tracing::Instrument::instrument(future, self.span.clone());
+        future.await
+    }
+
+    /// Record a fetched page with the given number of items.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn record_page(&mut self, items: usize) {
+        #[cfg(feature = "tracing")]
+        {
+            self.pages += 1;
+            self.items += items as u64;
+            self.span.record("pages", self.pages);
+            self.span.record("items", self.items);
+        }
+    }
+}
+
+/// Encode query parameters, redacting credential-like values.
+#[cfg(feature = "tracing")]
+fn sanitize_query(query: &[(String, String)]) -> String {
+    let sanitized: Vec<(&str, &str)> = query
+        .iter()
+        .map(|(name, value)| {
+            let lower = name.to_ascii_lowercase();
+            if SENSITIVE_PARAMS.iter().any(|s| lower.contains(s)) {
+                (name.as_str(), "[REDACTED]")
+            } else {
+                (name.as_str(), value.as_str())
+            }
+        })
+        .collect();
+    serde_urlencoded::to_string(sanitized).unwrap_or_default()
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use super::*;
+    use crate::client::EarningsFeed;
+    use crate::models::ListFilingsParams;
+    use futures::StreamExt;
+    use std::pin::pin;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// Minimal subscriber recording span names and every field value.
+    #[derive(Clone, Default)]
+    struct Capture {
+        lines: Arc<Mutex<Vec<String>>>,
+        next_id: Arc<Mutex<u64>>,
+    }
+
+    struct Fields<'a>(&'a mut String);
+
+    impl Visit for Fields<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0.push_str(&format!(" {}={:?}", field.name(), value));
+        }
+    }
+
+    impl Subscriber for Capture {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut line = span.metadata().name().to_string();
+            span.record(&mut Fields(&mut line));
+            self.lines.lock().unwrap().push(line);
+            let mut id = self.next_id.lock().unwrap();
+            *id += 1;
+            Id::from_u64(*id)
+        }
+
+        fn record(&self, _span: &Id, values: &Record<'_>) {
+            let mut line = "record".to_string();
+            values.record(&mut Fields(&mut line));
+            self.lines.lock().unwrap().push(line);
+        }
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut line = "event".to_string();
+            event.record(&mut Fields(&mut line));
+            self.lines.lock().unwrap().push(line);
+        }
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    #[test]
+    fn test_sanitize_query_redacts_credentials() {
+        let query = vec![
+            ("api_key".to_string(), "secret_value".to_string()),
+            ("ticker".to_string(), "AAPL".to_string()),
+            ("accessToken".to_string(), "abc".to_string()),
+        ];
+        assert_eq!(
+            sanitize_query(&query),
+            "api_key=%5BREDACTED%5D&ticker=AAPL&accessToken=%5BREDACTED%5D"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_spans_record_request_and_pagination() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/filings"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [],
+                "nextCursor": null,
+                "hasMore": false
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = EarningsFeed::builder()
+            .api_key("super_secret_api_key")
+            .base_url(mock_server.uri())
+            .build()
+            .unwrap();
+        let client = EarningsFeed::with_config(config).unwrap();
+
+        let capture = Capture::default();
+        let _guard = tracing::subscriber::set_default(capture.clone());
+
+        let params = ListFilingsParams::builder().ticker("AAPL").build();
+        let filings = client.filings();
+        let items: Vec<_> = pin!(filings.iter(params)).collect().await;
+        assert!(items.is_empty());
+
+        let lines = capture.lines.lock().unwrap().join("\n");
+        assert!(lines.contains("earningsfeed.paginate path=/api/v1/filings"));
+        assert!(lines.contains(
+            "earningsfeed.request http.method=GET path=/api/v1/filings query=ticker=AAPL"
+        ));
+        assert!(lines.contains("record attempt=1"));
+        assert!(lines.contains("record status=200"));
+        assert!(lines.contains("record pages=1"));
+        assert!(!lines.contains("super_secret_api_key"));
+    }
+}
@@ -0,0 +1,168 @@
+//! HTTP transport options for the EarningsFeed client.
+//!
+//! These options are set through [`ClientConfigBuilder`](crate::ClientConfigBuilder)
+//! and control how the underlying [`reqwest::Client`] is built.
+
+use std::time::Duration;
+
+use reqwest::{Certificate, Client, Proxy};
+
+use crate::error::{Error, Result};
+
+/// HTTP transport options.
+///
+/// Options left unset use the defaults of [`reqwest`]. In particular, the
+/// standard `HTTP_PROXY`, `HTTPS_PROXY` and `NO_PROXY` environment variables
+/// are honored unless a proxy is set explicitly.
+#[derive(Debug, Clone, Default)]
+pub struct TransportConfig {
+    /// Proxy for all requests.
+    pub proxy: Option<Proxy>,
+    /// Timeout for establishing connections.
+    pub connect_timeout: Option<Duration>,
+    /// How long idle pooled connections are kept alive.
+    pub pool_idle_timeout: Option<Duration>,
+    /// Maximum number of idle pooled connections per host.
+    pub pool_max_idle_per_host: Option<usize>,
+    /// Whether to request gzip-compressed responses (requires the `gzip` feature).
+    ///
+    /// Enabled by default when the feature is enabled.
+    pub gzip: Option<bool>,
+    /// Whether to request brotli-compressed responses (requires the `brotli` feature).
+    ///
+    /// Enabled by default when the feature is enabled.
+    pub brotli: Option<bool>,
+    /// Whether to use HTTP/2 without negotiation (requires the `http2` feature).
+    pub http2_prior_knowledge: bool,
+    /// Additional trusted root certificates.
+    pub root_certificates: Vec<Certificate>,
+    /// Pre-built HTTP client to use instead of building one.
+    ///
+    /// Cannot be combined with the other options.
+    pub http_client: Option<Client>,
+}
+
+impl TransportConfig {
+    /// Check that the options are supported by the enabled features.
+    pub(crate) fn validate(&self) -> Result<()> {
+        let requires = |option: &str, feature: &str| {
+            Err(Error::Config(format!(
+                "{} requires the `{}` feature",
+                option, feature
+            )))
+        };
+        if self.gzip == Some(true) && !cfg!(feature = "gzip") {
+            return requires("gzip compression", "gzip");
+        }
+        if self.brotli == Some(true) && !cfg!(feature = "brotli") {
+            return requires("brotli compression", "brotli");
+        }
+        if self.http2_prior_knowledge && !cfg!(feature = "http2") {
+            return requires("HTTP/2 prior knowledge", "http2");
+        }
+
+        if self.http_client.is_some() && !self.is_default() {
+            return Err(Error::Config(
+                "transport options cannot be combined with a custom HTTP client".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether no option other than `http_client` is set.
+    fn is_default(&self) -> bool {
+        self.proxy.is_none()
+            && self.connect_timeout.is_none()
+            && self.pool_idle_timeout.is_none()
+            && self.pool_max_idle_per_host.is_none()
+            && self.gzip.is_none()
+            && self.brotli.is_none()
+            && !self.http2_prior_knowledge
+            && self.root_certificates.is_empty()
+    }
+
+    /// Build the HTTP client, or return the pre-built one.
+    pub(crate) fn build_client(self) -> Result<Client> {
+        if let Some(client) = self.http_client {
+            return Ok(client);
+        }
+
+        let mut builder = Client::builder();
+        if let Some(proxy) = self.proxy {
+            builder = builder.proxy(proxy);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        #[cfg(feature = "gzip")]
+        if let Some(enabled) = self.gzip {
+            builder = builder.gzip(enabled);
+        }
+        #[cfg(feature = "brotli")]
+        if let Some(enabled) = self.brotli {
+            builder = builder.brotli(enabled);
+        }
+        #[cfg(feature = "http2")]
+        if self.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+        for certificate in self.root_certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_builds_client() {
+        let config = TransportConfig::default();
+        assert!(config.validate().is_ok());
+        assert!(config.build_client().is_ok());
+    }
+
+    #[test]
+    fn test_custom_client_conflicts_with_options() {
+        let config = TransportConfig {
+            http_client: Some(Client::new()),
+            connect_timeout: Some(Duration::from_secs(5)),
+            ..Default::default()
+        };
+        let err = config.validate().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "configuration error: transport options cannot be combined with a custom HTTP client"
+        );
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    #[test]
+    fn test_gzip_requires_feature() {
+        let config = TransportConfig {
+            gzip: Some(true),
+            ..Default::default()
+        };
+        let err = config.validate().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "configuration error: gzip compression requires the `gzip` feature"
+        );
+
+        // Disabling compression is always allowed.
+        let config = TransportConfig {
+            gzip: Some(false),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+    }
+}